@@ -0,0 +1,32 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use micro_rand::{
+    Pcg64, Random, RandomExt, RandomSource, SeedableRandom, Sfc32, Sfc64, WyRand,
+    Xoshiro256StarStar,
+};
+
+/// Time `next_u64`, `next_f64` and a small integer range for one generator
+fn bench<R: RandomSource>(c: &mut Criterion, name: &str, mut r: R) {
+    let mut group = c.benchmark_group(name);
+    group.bench_function("next_u64", |b| b.iter(|| black_box(r.next_u64())));
+    group.bench_function("next_f64", |b| b.iter(|| black_box(r.next_f64())));
+    group.bench_function("next_int_u32", |b| {
+        b.iter(|| black_box(r.next_int_u32(0, 100)))
+    });
+    group.finish();
+}
+
+fn generators(c: &mut Criterion) {
+    bench(c, "Random", Random::new(1234));
+    bench(c, "WyRand", WyRand::new(1234));
+    bench(c, "Sfc64", Sfc64::new(1234));
+    bench(c, "Sfc32", Sfc32::new(1234));
+    bench(c, "Pcg64", Pcg64::new(1234, 0));
+    bench(
+        c,
+        "Xoshiro256StarStar",
+        Xoshiro256StarStar::seed_from_u64(1234),
+    );
+}
+
+criterion_group!(benches, generators);
+criterion_main!(benches);
@@ -0,0 +1,423 @@
+use crate::split;
+use crate::{CryptoRandom, RandomSource, SeedableRandom, SplitRandom};
+
+/// The "expand 32-byte k" constant that starts every block
+const CONSTANTS: [u32; 4] = [0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574];
+
+/// The word position wraps around after `2^68` words
+const WORD_POS_MASK: u128 = (1 << 68) - 1;
+
+/// Run the ChaCha block function with `rounds` rounds on a full input state
+fn block(input: &[u32; 16], rounds: usize) -> [u32; 16] {
+    let mut s = *input;
+    for _ in 0..rounds / 2 {
+        quarter_round(&mut s, 0, 4, 8, 12);
+        quarter_round(&mut s, 1, 5, 9, 13);
+        quarter_round(&mut s, 2, 6, 10, 14);
+        quarter_round(&mut s, 3, 7, 11, 15);
+
+        quarter_round(&mut s, 0, 5, 10, 15);
+        quarter_round(&mut s, 1, 6, 11, 12);
+        quarter_round(&mut s, 2, 7, 8, 13);
+        quarter_round(&mut s, 3, 4, 9, 14);
+    }
+
+    for (i, j) in s.iter_mut().zip(input.iter()) {
+        *i = i.wrapping_add(*j);
+    }
+    s
+}
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]);
+    s[d] = (s[d] ^ s[a]).rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+/// Define a ChaCha generator with a set number of rounds
+///
+/// The state is laid out like the original ChaCha, with a 64 bit block counter
+/// followed by a 64 bit stream id, and the output is the keystream one word at a time.
+macro_rules! impl_chacha {
+    ($name:ident, $rounds:expr, $first:expr) => {
+        #[doc = concat!("ChaCha", stringify!($rounds), " Generator")]
+        ///
+        #[doc = concat!("A cryptographically secure generator using the ChaCha stream cipher with ", stringify!($rounds), " rounds.")]
+        /// The output is the cipher's keystream for a 256 bit key,
+        /// with `2^64` separate streams of `2^68` words each.
+        /// ## Example
+        /// ```rust
+        /// // Import Lib
+        #[doc = concat!("use micro_rand::{", stringify!($name), ", RandomSource};")]
+        ///
+        /// // Make a new generator from a key, on stream 0
+        #[doc = concat!("let mut r = ", stringify!($name), "::new([0; 32], 0);")]
+        #[doc = concat!("assert_eq!(r.next_u32(), ", stringify!($first), ");")]
+        /// ```
+        #[derive(Clone)]
+        pub struct $name {
+            key: [u32; 8],
+            stream: u64,
+            counter: u64,
+            buffer: [u32; 16],
+            index: usize,
+        }
+
+        impl $name {
+            #[doc = concat!("Make a new ", stringify!($name), " generator from a key and a stream id")]
+            ///
+            /// The key is read as little endian words, just like the cipher.
+            /// Generators with the same key but different streams give unrelated output.
+            pub const fn new(key: [u8; 32], stream: u64) -> $name {
+                let mut k = [0; 8];
+                let mut i = 0;
+                while i < 8 {
+                    k[i] = u32::from_le_bytes([key[4 * i], key[4 * i + 1], key[4 * i + 2], key[4 * i + 3]]);
+                    i += 1;
+                }
+
+                $name {
+                    key: k,
+                    stream,
+                    counter: 0,
+                    buffer: [0; 16],
+                    index: 16,
+                }
+            }
+
+            /// Get the stream id
+            pub fn stream(&self) -> u64 {
+                self.stream
+            }
+
+            /// Switch to another stream, keeping the same word position
+            pub fn set_stream(&mut self, stream: u64) {
+                let pos = self.word_pos();
+                self.stream = stream;
+                self.set_word_pos(pos);
+            }
+
+            /// Get the number of 32 bit words output so far in this stream
+            pub fn word_pos(&self) -> u128 {
+                ((self.counter as u128) << 4)
+                    .wrapping_add(self.index as u128)
+                    .wrapping_sub(16)
+                    & WORD_POS_MASK
+            }
+
+            /// Seek to any word in the stream
+            ///
+            /// Only the low 68 bits of `pos` are used, as that's the length of a stream.
+            /// ## Example
+            /// ```rust
+            /// // Import Lib
+            #[doc = concat!("use micro_rand::{", stringify!($name), ", RandomSource};")]
+            ///
+            /// // Skip the first block of 16 words
+            #[doc = concat!("let mut a = ", stringify!($name), "::new([0; 32], 0);")]
+            #[doc = concat!("let mut b = ", stringify!($name), "::new([0; 32], 0);")]
+            /// for _ in 0..16 {
+            ///     a.next_u32();
+            /// }
+            /// b.set_word_pos(16);
+            /// assert_eq!(a.next_u32(), b.next_u32());
+            /// ```
+            pub fn set_word_pos(&mut self, pos: u128) {
+                let pos = pos & WORD_POS_MASK;
+                self.counter = (pos >> 4) as u64;
+                self.refill();
+                self.index = (pos & 15) as usize;
+            }
+
+            /// Make the block at the counter, then move the counter on
+            fn refill(&mut self) {
+                let mut input = [0; 16];
+                input[..4].copy_from_slice(&CONSTANTS);
+                input[4..12].copy_from_slice(&self.key);
+                input[12] = self.counter as u32;
+                input[13] = (self.counter >> 32) as u32;
+                input[14] = self.stream as u32;
+                input[15] = (self.stream >> 32) as u32;
+
+                self.buffer = block(&input, $rounds);
+                self.counter = self.counter.wrapping_add(1);
+                self.index = 0;
+            }
+        }
+
+        impl RandomSource for $name {
+            fn next_u32(&mut self) -> u32 {
+                if self.index >= 16 {
+                    self.refill();
+                }
+
+                let out = self.buffer[self.index];
+                self.index += 1;
+                out
+            }
+
+            /// Made from two outputs, high bits first
+            fn next_u64(&mut self) -> u64 {
+                (self.next_u32() as u64) << 32 | self.next_u32() as u64
+            }
+
+            /// Fills the buffer with the keystream bytes, in order
+            ///
+            /// Every 4 bytes (or part of 4 bytes) uses up one word.
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                for chunk in dest.chunks_mut(4) {
+                    let bytes = self.next_u32().to_le_bytes();
+                    chunk.copy_from_slice(&bytes[..chunk.len()]);
+                }
+            }
+        }
+
+        impl SeedableRandom for $name {
+            type Seed = [u8; 32];
+
+            /// The seed is the key, on stream 0
+            ///
+            /// Note that [`SeedableRandom::seed_from_u64`] only has 64 bits of entropy,
+            /// so it should not be used for anything secret.
+            fn from_seed(seed: [u8; 32]) -> $name {
+                $name::new(seed, 0)
+            }
+        }
+
+        impl SplitRandom for $name {
+            /// The child is keyed with the next 32 bytes of output, on stream 0
+            fn fork(&mut self) -> $name {
+                split::fork_from_seed(self)
+            }
+        }
+
+        impl CryptoRandom for $name {}
+
+        /// Generators are equal when they have the same key, stream and word position,
+        /// however they got there
+        impl PartialEq for $name {
+            fn eq(&self, other: &$name) -> bool {
+                self.key == other.key
+                    && self.stream == other.stream
+                    && self.word_pos() == other.word_pos()
+            }
+        }
+
+        impl Eq for $name {}
+
+        /// Hashes the key, stream and word position, to match [`PartialEq`]
+        impl core::hash::Hash for $name {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                self.key.hash(state);
+                self.stream.hash(state);
+                self.word_pos().hash(state);
+            }
+        }
+
+        /// Shows the stream and position, but not the key
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("stream", &self.stream)
+                    .field("word_pos", &self.word_pos())
+                    .finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+impl_chacha!(ChaCha8Rng, 8, 0x2fef003e);
+impl_chacha!(ChaCha12Rng, 12, 0x6a9af49b);
+impl_chacha!(ChaCha20Rng, 20, 0xade0b876);
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::hash::{Hash, Hasher};
+    use std::collections::hash_map::DefaultHasher;
+    use std::format;
+
+    use super::{block, ChaCha12Rng, ChaCha20Rng, ChaCha8Rng, CONSTANTS};
+    use crate::{CryptoRandom, RandomSource, SeedableRandom};
+
+    fn keystream<R: RandomSource>(r: &mut R) -> [u8; 64] {
+        let mut out = [0; 64];
+        r.fill_bytes(&mut out);
+        out
+    }
+
+    /// Read 64 bytes of hex, skipping spaces
+    fn hex(s: &str) -> [u8; 64] {
+        let mut out = [0; 64];
+        let mut digits = s.chars().filter_map(|i| i.to_digit(16));
+        for i in out.iter_mut() {
+            *i = (digits.next().unwrap() << 4 | digits.next().unwrap()) as u8;
+        }
+        out
+    }
+
+    #[test]
+    fn test_rfc8439_block() {
+        // RFC 8439 section 2.3.2, which uses a 32 bit counter and a 96 bit nonce
+        let mut input = [0; 16];
+        input[..4].copy_from_slice(&CONSTANTS);
+        for i in 0..8 {
+            input[4 + i] = u32::from_le_bytes([
+                4 * i as u8,
+                4 * i as u8 + 1,
+                4 * i as u8 + 2,
+                4 * i as u8 + 3,
+            ]);
+        }
+        input[12] = 1;
+        input[13] = 0x0900_0000;
+        input[14] = 0x4a00_0000;
+        input[15] = 0;
+
+        let expected = [
+            0xe4e7f110, 0x15593bd1, 0x1fdd0f50, 0xc47120a3, 0xc7f4d1c7, 0x0368c033, 0x9aaa2204,
+            0x4e6cd4c3, 0x466482d2, 0x09aa9f07, 0x05d7c214, 0xa2028bd9, 0xd19c12b5, 0xb94e16de,
+            0xe883d0cb, 0x4e3c50a2,
+        ];
+        assert_eq!(block(&input, 20), expected);
+    }
+
+    #[test]
+    fn test_rfc8439_keystream() {
+        // RFC 8439 appendix A.1, test vectors 1, 2 and 5
+        let mut r = ChaCha20Rng::new([0; 32], 0);
+        assert_eq!(
+            keystream(&mut r)[..],
+            hex("76b8e0ad a0f13d90 405d6ae5 5386bd28 bdd219b8 a08ded1a a836efcc 8b770dc7 da41597c 5157488d 7724e03f b8d84a37 6a43b8f4 1518a11c c387b669 b2ee6586")[..]
+        );
+        assert_eq!(
+            keystream(&mut r)[..],
+            hex("9f07e7be 5551387a 98ba977c 732d080d cb0f29a0 48e36569 12c6533e 32ee7aed 29b72176 9ce64e43 d57133b0 74d839d5 31ed1f28 510afb45 ace10a1f 4b794d6f")[..]
+        );
+
+        let mut r = ChaCha20Rng::new([0; 32], 0x0200_0000_0000_0000);
+        assert_eq!(
+            keystream(&mut r)[..],
+            hex("c2c64d37 8cd53637 4ae204b9 ef933fcd 1a8b2288 b3dfa496 72ab765b 54ee27c7 8a970e0e 955c14f3 a88e741b 97c286f7 5f8fc299 e8148362 fa198a39 531bed6d")[..]
+        );
+    }
+
+    #[test]
+    fn test_reduced_rounds() {
+        // From the ChaCha test vectors draft (draft-strombergson-chacha-test-vectors), TC1 with a 256 bit key
+        let mut r = ChaCha8Rng::new([0; 32], 0);
+        assert_eq!(
+            keystream(&mut r)[..],
+            hex("3e00ef2f895f40d67f5bb8e81f09a5a12c840ec3ce9a7f3b181be188ef711a1e984ce172b9216f419f445367456d5619314a42a3da86b001387bfdb80e0cfe42")[..]
+        );
+
+        let mut r = ChaCha12Rng::new([0; 32], 0);
+        assert_eq!(
+            keystream(&mut r)[..],
+            hex("9bf49a6a0755f953811fce125f2683d50429c3bb49e074147e0089a52eae155f0564f879d27ae3c02ce82834acfa8c793a629f2ca0de6919610be82f411326be")[..]
+        );
+    }
+
+    #[test]
+    fn test_word_pos() {
+        let mut a = ChaCha20Rng::new([7; 32], 3);
+        let mut b = ChaCha20Rng::new([7; 32], 3);
+        assert_eq!(a.word_pos(), 0);
+        for _ in 0..37 {
+            a.next_u32();
+        }
+        assert_eq!(a.word_pos(), 37);
+        b.set_word_pos(37);
+        assert_eq!(b.word_pos(), 37);
+        assert_eq!(a.next_u32(), b.next_u32());
+
+        // The stream can be switched without losing the position
+        a.set_stream(4);
+        assert_eq!(a.stream(), 4);
+        assert_eq!(a.word_pos(), 38);
+        let mut c = ChaCha20Rng::new([7; 32], 4);
+        c.set_word_pos(38);
+        assert_eq!(a.next_u32(), c.next_u32());
+
+        // The position wraps around at the end of the stream
+        let mut r = ChaCha20Rng::new([7; 32], 3);
+        r.set_word_pos((1 << 68) - 1);
+        assert_eq!(r.word_pos(), (1 << 68) - 1);
+        r.next_u32();
+        assert_eq!(r.word_pos(), 0);
+        let mut s = ChaCha20Rng::new([7; 32], 3);
+        assert_eq!(r.next_u32(), s.next_u32());
+    }
+
+    #[test]
+    fn test_seed() {
+        let mut a = ChaCha20Rng::from_seed([1; 32]);
+        let mut b = ChaCha20Rng::new([1; 32], 0);
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn test_crypto_marker() {
+        fn secure<R: CryptoRandom>(r: &mut R) -> u32 {
+            r.next_u32()
+        }
+
+        secure(&mut ChaCha8Rng::new([0; 32], 0));
+        secure(&mut ChaCha12Rng::new([0; 32], 0));
+        secure(&mut ChaCha20Rng::new([0; 32], 0));
+    }
+
+    #[test]
+    fn test_debug_hides_key() {
+        let mut r = ChaCha20Rng::new([0xab; 32], 7);
+        r.next_u32();
+        assert_eq!(
+            format!("{:?}", r),
+            "ChaCha20Rng { stream: 7, word_pos: 1, .. }"
+        );
+        assert_eq!(r.clone(), r);
+    }
+
+    #[test]
+    fn test_eq_by_position() {
+        let hash = |r: &ChaCha8Rng| {
+            let mut h = DefaultHasher::new();
+            r.hash(&mut h);
+            h.finish()
+        };
+
+        // Same key, stream and position, whether the block was made or not
+        let mut a = ChaCha8Rng::new([1; 32], 3);
+        let mut b = ChaCha8Rng::new([1; 32], 3);
+        for _ in 0..16 {
+            a.next_u32();
+        }
+        b.set_word_pos(16);
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+        let mut c = ChaCha8Rng::from_seed([1; 32]);
+        c.set_stream(3);
+        c.set_word_pos(16);
+        assert_eq!(a, c);
+
+        a.next_u32();
+        assert_ne!(a, b);
+        b.next_u32();
+        assert_eq!(a, b);
+        c.set_word_pos(17);
+        c.set_stream(4);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_const_new() {
+        static R: ChaCha20Rng = ChaCha20Rng::new([0; 32], 0);
+        assert_eq!(R.clone().next_u32(), 0xade0b876);
+    }
+}
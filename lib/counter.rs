@@ -0,0 +1,386 @@
+use core::convert::TryInto;
+
+use crate::split;
+use crate::{RandomSource, SeedableRandom, SplitRandom};
+
+const PHILOX_M0: u32 = 0xd251_1f53;
+const PHILOX_M1: u32 = 0xcd9e_8d57;
+const PHILOX_W0: u32 = 0x9e37_79b9;
+const PHILOX_W1: u32 = 0xbb67_ae85;
+
+const THREEFRY_PARITY: u64 = 0x1bd1_1bda_a9fc_1a22;
+const THREEFRY_ROTATIONS: [u32; 8] = [16, 42, 12, 31, 16, 32, 24, 21];
+
+/// A counter based generator
+///
+/// These have no changing state, the output is just a keyed function of a counter.
+/// So any block can be had directly, and separate threads can share a key with no coordination.
+/// Use [`CounterRng`] to get them one after another as a [`RandomSource`].
+pub trait CounterRandom {
+    /// Get the 128 bit block at counter `n`, as four 32 bit words
+    fn words_at(&self, n: u128) -> [u32; 4];
+}
+
+/// Philox4x32-10 Generator
+///
+/// A counter based generator from Random123, mapping a 128 bit counter and a 64 bit key
+/// to 128 bits of output with 10 rounds of multiplies and xors.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::Philox4x32;
+///
+/// // Get the random numbers for work item 12345, with no shared state
+/// let p = Philox4x32::new([1234, 5678]);
+/// let [a, b, c, d] = p.nth_block(12345);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Philox4x32 {
+    key: [u32; 2],
+}
+
+impl Philox4x32 {
+    /// Make a new Philox4x32-10 generator from its key
+    pub const fn new(key: [u32; 2]) -> Philox4x32 {
+        Philox4x32 { key }
+    }
+
+    /// Get the block for a counter
+    ///
+    /// This is the `philox4x32` function from Random123.
+    pub fn block(&self, counter: [u32; 4]) -> [u32; 4] {
+        let mut c = counter;
+        let mut k = self.key;
+        for round in 0..10 {
+            if round > 0 {
+                k[0] = k[0].wrapping_add(PHILOX_W0);
+                k[1] = k[1].wrapping_add(PHILOX_W1);
+            }
+
+            let p0 = PHILOX_M0 as u64 * c[0] as u64;
+            let p1 = PHILOX_M1 as u64 * c[2] as u64;
+            c = [
+                (p1 >> 32) as u32 ^ c[1] ^ k[0],
+                p1 as u32,
+                (p0 >> 32) as u32 ^ c[3] ^ k[1],
+                p0 as u32,
+            ];
+        }
+        c
+    }
+
+    /// Get the `n`th block
+    ///
+    /// The counter is `n` split into little endian words.
+    pub fn nth_block(&self, n: u128) -> [u32; 4] {
+        self.block([
+            n as u32,
+            (n >> 32) as u32,
+            (n >> 64) as u32,
+            (n >> 96) as u32,
+        ])
+    }
+}
+
+impl Default for Philox4x32 {
+    /// Same as `Philox4x32::new([0, 0])`
+    fn default() -> Philox4x32 {
+        Philox4x32::new([0, 0])
+    }
+}
+
+impl CounterRandom for Philox4x32 {
+    fn words_at(&self, n: u128) -> [u32; 4] {
+        self.nth_block(n)
+    }
+}
+
+/// Threefry-2x64-20 Generator
+///
+/// A counter based generator from Random123, mapping a 128 bit counter and a 128 bit key
+/// to 128 bits of output with 20 rounds of the Threefish add / rotate / xor mix.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::Threefry2x64;
+///
+/// // Get the random numbers for work item 12345, with no shared state
+/// let t = Threefry2x64::new([1234, 5678]);
+/// let [a, b] = t.nth_block(12345);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Threefry2x64 {
+    key: [u64; 3],
+}
+
+impl Threefry2x64 {
+    /// Make a new Threefry-2x64-20 generator from its key
+    pub const fn new(key: [u64; 2]) -> Threefry2x64 {
+        Threefry2x64 {
+            key: [key[0], key[1], THREEFRY_PARITY ^ key[0] ^ key[1]],
+        }
+    }
+
+    /// Get the block for a counter
+    ///
+    /// This is the `threefry2x64` function from Random123.
+    pub fn block(&self, counter: [u64; 2]) -> [u64; 2] {
+        let ks = &self.key;
+        let mut x = [
+            counter[0].wrapping_add(ks[0]),
+            counter[1].wrapping_add(ks[1]),
+        ];
+
+        for round in 0..20 {
+            x[0] = x[0].wrapping_add(x[1]);
+            x[1] = x[1].rotate_left(THREEFRY_ROTATIONS[round % 8]) ^ x[0];
+
+            if round % 4 == 3 {
+                let s = (round + 1) / 4;
+                x[0] = x[0].wrapping_add(ks[s % 3]);
+                x[1] = x[1].wrapping_add(ks[(s + 1) % 3]).wrapping_add(s as u64);
+            }
+        }
+        x
+    }
+
+    /// Get the `n`th block
+    ///
+    /// The counter is `n` split into little endian words.
+    pub fn nth_block(&self, n: u128) -> [u64; 2] {
+        self.block([n as u64, (n >> 64) as u64])
+    }
+}
+
+impl Default for Threefry2x64 {
+    /// Same as `Threefry2x64::new([0, 0])`
+    fn default() -> Threefry2x64 {
+        Threefry2x64::new([0, 0])
+    }
+}
+
+impl CounterRandom for Threefry2x64 {
+    /// The two 64 bit words split into 32 bit words, low bits first
+    fn words_at(&self, n: u128) -> [u32; 4] {
+        let [a, b] = self.nth_block(n);
+        [a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32]
+    }
+}
+
+/// Sequential adapter for a counter based generator
+///
+/// Goes through the blocks in order, starting from block 0,
+/// and implements [`RandomSource`] on top of them.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{CounterRng, Philox4x32, RandomExt};
+///
+/// // Use Philox like any other generator
+/// let mut r = CounterRng::new(Philox4x32::new([1234, 5678]));
+/// let i = r.next_int_u8(1, 6);
+///
+/// // Or start from any block
+/// let mut r = CounterRng::with_counter(Philox4x32::new([1234, 5678]), 1_000_000);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CounterRng<G> {
+    generator: G,
+    counter: u128,
+    buffer: [u32; 4],
+    index: usize,
+}
+
+impl<G: CounterRandom> CounterRng<G> {
+    /// Make a new adapter, starting at block 0
+    pub const fn new(generator: G) -> CounterRng<G> {
+        CounterRng::with_counter(generator, 0)
+    }
+
+    /// Make a new adapter, starting at block `counter`
+    pub const fn with_counter(generator: G, counter: u128) -> CounterRng<G> {
+        CounterRng {
+            generator,
+            counter,
+            buffer: [0; 4],
+            index: 4,
+        }
+    }
+
+    /// Get the counter of the next block to be made
+    pub fn counter(&self) -> u128 {
+        self.counter
+    }
+
+    /// Get the wrapped generator
+    pub fn generator(&self) -> &G {
+        &self.generator
+    }
+}
+
+impl<G: CounterRandom + Default> Default for CounterRng<G> {
+    /// The default generator, starting at block 0
+    fn default() -> CounterRng<G> {
+        CounterRng::new(G::default())
+    }
+}
+
+impl<G: CounterRandom> RandomSource for CounterRng<G> {
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= 4 {
+            self.buffer = self.generator.words_at(self.counter);
+            self.counter = self.counter.wrapping_add(1);
+            self.index = 0;
+        }
+
+        let out = self.buffer[self.index];
+        self.index += 1;
+        out
+    }
+
+    /// Made from two words, low bits first
+    ///
+    /// So with [`Threefry2x64`] this gives its 64 bit words as they are.
+    fn next_u64(&mut self) -> u64 {
+        self.next_u32() as u64 | (self.next_u32() as u64) << 32
+    }
+}
+
+impl SeedableRandom for CounterRng<Philox4x32> {
+    type Seed = [u8; 8];
+
+    /// The seed is the key, as little endian words
+    fn from_seed(seed: [u8; 8]) -> CounterRng<Philox4x32> {
+        let a = u32::from_le_bytes(seed[..4].try_into().unwrap());
+        let b = u32::from_le_bytes(seed[4..].try_into().unwrap());
+        CounterRng::new(Philox4x32::new([a, b]))
+    }
+}
+
+impl SeedableRandom for CounterRng<Threefry2x64> {
+    type Seed = [u8; 16];
+
+    /// The seed is the key, as little endian words
+    fn from_seed(seed: [u8; 16]) -> CounterRng<Threefry2x64> {
+        let a = u64::from_le_bytes(seed[..8].try_into().unwrap());
+        let b = u64::from_le_bytes(seed[8..].try_into().unwrap());
+        CounterRng::new(Threefry2x64::new([a, b]))
+    }
+}
+
+impl SplitRandom for CounterRng<Philox4x32> {
+    /// The child is keyed with the next 8 bytes of output, starting at block 0
+    fn fork(&mut self) -> CounterRng<Philox4x32> {
+        split::fork_from_seed(self)
+    }
+}
+
+impl SplitRandom for CounterRng<Threefry2x64> {
+    /// The child is keyed with the next 16 bytes of output, starting at block 0
+    fn fork(&mut self) -> CounterRng<Threefry2x64> {
+        split::fork_from_seed(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CounterRandom, CounterRng, Philox4x32, Threefry2x64};
+    use crate::{RandomSource, SeedableRandom};
+
+    #[test]
+    fn test_philox_known_answer() {
+        // From the Random123 known answer tests, kat_vectors
+        let p = Philox4x32::new([0, 0]);
+        assert_eq!(
+            p.block([0; 4]),
+            [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]
+        );
+
+        let p = Philox4x32::new([u32::MAX; 2]);
+        assert_eq!(
+            p.block([u32::MAX; 4]),
+            [0x408f276d, 0x41c83b0e, 0xa20bc7c6, 0x6d5451fd]
+        );
+
+        let p = Philox4x32::new([0xa4093822, 0x299f31d0]);
+        assert_eq!(
+            p.block([0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344]),
+            [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1]
+        );
+    }
+
+    #[test]
+    fn test_threefry_known_answer() {
+        // From the Random123 known answer tests, kat_vectors
+        let t = Threefry2x64::new([0, 0]);
+        assert_eq!(t.block([0; 2]), [0xc2b6e3a8c2c69865, 0x6f81ed42f350084d]);
+
+        let t = Threefry2x64::new([u64::MAX; 2]);
+        assert_eq!(
+            t.block([u64::MAX; 2]),
+            [0xe02cb7c4d95d277a, 0xd06633d0893b8b68]
+        );
+
+        let t = Threefry2x64::new([0xa4093822299f31d0, 0x082efa98ec4e6c89]);
+        assert_eq!(
+            t.block([0x243f6a8885a308d3, 0x13198a2e03707344]),
+            [0x263c7d30bb0f0af1, 0x56be8361d3311526]
+        );
+    }
+
+    #[test]
+    fn test_nth_block() {
+        let n = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210;
+        let p = Philox4x32::new([1, 2]);
+        assert_eq!(
+            p.nth_block(n),
+            p.block([0x7654_3210, 0xfedc_ba98, 0x89ab_cdef, 0x0123_4567])
+        );
+
+        let t = Threefry2x64::new([1, 2]);
+        assert_eq!(
+            t.nth_block(n),
+            t.block([0xfedc_ba98_7654_3210, 0x0123_4567_89ab_cdef])
+        );
+        let [a, b] = t.nth_block(n);
+        assert_eq!(
+            t.words_at(n),
+            [a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32]
+        );
+    }
+
+    #[test]
+    fn test_sequential() {
+        let mut r = CounterRng::new(Philox4x32::new([1, 2]));
+        let p = Philox4x32::new([1, 2]);
+        for n in 0..10 {
+            for word in p.nth_block(n).iter() {
+                assert_eq!(r.next_u32(), *word);
+            }
+        }
+        assert_eq!(r.counter(), 10);
+
+        let mut r = CounterRng::with_counter(Threefry2x64::new([1, 2]), 5);
+        let t = Threefry2x64::new([1, 2]);
+        for n in 5..15 {
+            for word in t.nth_block(n).iter() {
+                assert_eq!(r.next_u64(), *word);
+            }
+        }
+        assert_eq!(r.generator().nth_block(0), t.nth_block(0));
+    }
+
+    #[test]
+    fn test_seed() {
+        let mut a = CounterRng::<Philox4x32>::from_seed([1, 0, 0, 0, 2, 0, 0, 0]);
+        let mut b = CounterRng::new(Philox4x32::new([1, 2]));
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        let mut seed = [0; 16];
+        seed[0] = 1;
+        seed[8] = 2;
+        let mut a = CounterRng::<Threefry2x64>::from_seed(seed);
+        let mut b = CounterRng::new(Threefry2x64::new([1, 2]));
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+}
@@ -0,0 +1,138 @@
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+const RCON: [u8; 7] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40];
+
+/// AES-256, encryption only
+///
+/// Uses a table for the S-box, so it is not constant time.
+pub(crate) struct Aes256 {
+    round_keys: [[u8; 16]; 15],
+}
+
+impl Aes256 {
+    pub(crate) fn new(key: &[u8; 32]) -> Aes256 {
+        let mut w = [[0_u8; 4]; 60];
+        for (i, j) in w.iter_mut().zip(key.chunks_exact(4)) {
+            i.copy_from_slice(j);
+        }
+
+        for i in 8..60 {
+            let mut t = w[i - 1];
+            if i % 8 == 0 {
+                t = [
+                    SBOX[t[1] as usize] ^ RCON[i / 8 - 1],
+                    SBOX[t[2] as usize],
+                    SBOX[t[3] as usize],
+                    SBOX[t[0] as usize],
+                ];
+            } else if i % 8 == 4 {
+                for b in t.iter_mut() {
+                    *b = SBOX[*b as usize];
+                }
+            }
+            for (b, p) in t.iter_mut().zip(w[i - 8].iter()) {
+                *b ^= p;
+            }
+            w[i] = t;
+        }
+
+        let mut round_keys = [[0; 16]; 15];
+        for (i, j) in round_keys.iter_mut().zip(w.chunks_exact(4)) {
+            for (k, l) in i.chunks_exact_mut(4).zip(j.iter()) {
+                k.copy_from_slice(l);
+            }
+        }
+        Aes256 { round_keys }
+    }
+
+    pub(crate) fn encrypt(&self, block: &[u8; 16]) -> [u8; 16] {
+        let mut s = *block;
+        add_round_key(&mut s, &self.round_keys[0]);
+        for round in 1..15 {
+            for b in s.iter_mut() {
+                *b = SBOX[*b as usize];
+            }
+            shift_rows(&mut s);
+            if round != 14 {
+                mix_columns(&mut s);
+            }
+            add_round_key(&mut s, &self.round_keys[round]);
+        }
+        s
+    }
+}
+
+fn add_round_key(s: &mut [u8; 16], key: &[u8; 16]) {
+    for (i, j) in s.iter_mut().zip(key.iter()) {
+        *i ^= j;
+    }
+}
+
+/// The state is stored column by column, so row `r` is every 4th byte from `r`
+fn shift_rows(s: &mut [u8; 16]) {
+    let t = *s;
+    for c in 0..4 {
+        for r in 1..4 {
+            s[4 * c + r] = t[4 * ((c + r) % 4) + r];
+        }
+    }
+}
+
+fn mix_columns(s: &mut [u8; 16]) {
+    for c in s.chunks_exact_mut(4) {
+        let [a, b, d, e] = [c[0], c[1], c[2], c[3]];
+        let all = a ^ b ^ d ^ e;
+        c[0] ^= all ^ xtime(a ^ b);
+        c[1] ^= all ^ xtime(b ^ d);
+        c[2] ^= all ^ xtime(d ^ e);
+        c[3] ^= all ^ xtime(e ^ a);
+    }
+}
+
+/// Multiply by 2 in GF(2^8)
+fn xtime(x: u8) -> u8 {
+    (x << 1) ^ ((x >> 7) * 0x1b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Aes256;
+
+    #[test]
+    fn test_fips197() {
+        // From FIPS 197, appendix C.3
+        let mut key = [0; 32];
+        for (i, j) in key.iter_mut().enumerate() {
+            *j = i as u8;
+        }
+        let mut block = [0; 16];
+        for (i, j) in block.iter_mut().enumerate() {
+            *j = (i as u8) << 4 | i as u8;
+        }
+
+        assert_eq!(
+            Aes256::new(&key).encrypt(&block),
+            [
+                0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49,
+                0x60, 0x89
+            ]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use core::convert::TryInto;
+
+use super::aes::Aes256;
+use crate::error::DrbgError;
+#[cfg(feature = "getrandom")]
+use crate::error::EntropyError;
+use crate::{CryptoRandom, RandomSource, SeedableRandom};
+
+/// The seed length for AES-256, in bytes
+const SEED_LEN: usize = 48;
+
+/// CTR_DRBG Generator
+///
+/// The CTR_DRBG from NIST SP 800-90A, using AES-256 with the derivation function.
+/// It keeps a key and a counter `V`, and outputs the encrypted counter.
+///
+/// The AES used here looks up the S-box in a table, so it is not constant time.
+/// Where cache timing attacks are a concern, [`HashDrbg`](crate::HashDrbg) or
+/// [`HmacDrbg`](crate::HmacDrbg) are a better choice.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::CtrDrbg;
+///
+/// // Instantiate, then generate with some additional input
+/// let mut r = CtrDrbg::new(&[7; 32], &[8; 16], &[], false).unwrap();
+/// let mut buf = [0; 64];
+/// r.generate(&mut buf, b"request 1").unwrap();
+///
+/// // Mix in fresh entropy later
+/// r.reseed(&[9; 32], &[]).unwrap();
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CtrDrbg {
+    key: [u8; 32],
+    v: [u8; 16],
+    reseed_counter: u64,
+    prediction_resistance: bool,
+}
+
+impl CtrDrbg {
+    fn instantiate_alg(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> CtrDrbg {
+        let mut drbg = CtrDrbg {
+            key: [0; 32],
+            v: [0; 16],
+            reseed_counter: 1,
+            prediction_resistance: false,
+        };
+        drbg.update(&block_cipher_df(&[entropy, nonce, personalization]));
+        drbg
+    }
+
+    fn reseed_alg(&mut self, entropy: &[u8], additional: &[u8]) {
+        self.update(&block_cipher_df(&[entropy, additional]));
+    }
+
+    fn generate_alg(&mut self, out: &mut [u8], additional: &[u8]) {
+        let additional = match additional.is_empty() {
+            true => [0; SEED_LEN],
+            false => {
+                let additional = block_cipher_df(&[additional]);
+                self.update(&additional);
+                additional
+            }
+        };
+
+        let aes = Aes256::new(&self.key);
+        for chunk in out.chunks_mut(16) {
+            increment(&mut self.v);
+            chunk.copy_from_slice(&aes.encrypt(&self.v)[..chunk.len()]);
+        }
+
+        self.update(&additional);
+    }
+
+    /// The CTR_DRBG_Update function, mixing a full seed into the key and `V`
+    fn update(&mut self, provided: &[u8; SEED_LEN]) {
+        let aes = Aes256::new(&self.key);
+        let mut temp = [0; SEED_LEN];
+        for chunk in temp.chunks_exact_mut(16) {
+            increment(&mut self.v);
+            chunk.copy_from_slice(&aes.encrypt(&self.v));
+        }
+
+        for (i, j) in temp.iter_mut().zip(provided.iter()) {
+            *i ^= j;
+        }
+        self.key.copy_from_slice(&temp[..32]);
+        self.v.copy_from_slice(&temp[32..]);
+    }
+}
+
+impl_drbg!(CtrDrbg);
+
+/// Add one to a big endian counter, wrapping around
+fn increment(v: &mut [u8; 16]) {
+    *v = (u128::from_be_bytes(*v).wrapping_add(1)).to_be_bytes();
+}
+
+/// The Block_Cipher_df derivation function, making a full seed from a list of byte strings
+fn block_cipher_df(parts: &[&[u8]]) -> [u8; SEED_LEN] {
+    let len = parts.iter().map(|i| i.len()).sum::<usize>() as u32;
+
+    let mut key = [0; 32];
+    for (i, j) in key.iter_mut().enumerate() {
+        *j = i as u8;
+    }
+    let aes = Aes256::new(&key);
+
+    let mut temp = [0; SEED_LEN];
+    for (i, chunk) in temp.chunks_exact_mut(16).enumerate() {
+        let mut iv = [0; 16];
+        iv[..4].copy_from_slice(&(i as u32).to_be_bytes());
+        let header = [len.to_be_bytes(), (SEED_LEN as u32).to_be_bytes()];
+
+        let mut bcc = Bcc::new(&aes);
+        bcc.update(&iv);
+        bcc.update(&header[0]);
+        bcc.update(&header[1]);
+        for j in parts {
+            bcc.update(j);
+        }
+        bcc.update(&[0x80]);
+        chunk.copy_from_slice(&bcc.finish());
+    }
+
+    let aes = Aes256::new(&temp[..32].try_into().unwrap());
+    let mut x: [u8; 16] = temp[32..].try_into().unwrap();
+    let mut out = [0; SEED_LEN];
+    for chunk in out.chunks_exact_mut(16) {
+        x = aes.encrypt(&x);
+        chunk.copy_from_slice(&x);
+    }
+    out
+}
+
+/// The BCC function, a CBC-MAC fed in pieces and zero padded at the end
+struct Bcc<'a> {
+    aes: &'a Aes256,
+    chain: [u8; 16],
+    used: usize,
+}
+
+impl<'a> Bcc<'a> {
+    fn new(aes: &'a Aes256) -> Bcc<'a> {
+        Bcc {
+            aes,
+            chain: [0; 16],
+            used: 0,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for i in data {
+            self.chain[self.used] ^= i;
+            self.used += 1;
+            if self.used == 16 {
+                self.chain = self.aes.encrypt(&self.chain);
+                self.used = 0;
+            }
+        }
+    }
+
+    fn finish(mut self) -> [u8; 16] {
+        if self.used != 0 {
+            self.chain = self.aes.encrypt(&self.chain);
+        }
+        self.chain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CtrDrbg;
+    use crate::drbg::unhex;
+    use crate::error::DrbgError;
+
+    #[test]
+    fn test_cavp_reseed() {
+        // From the NIST CAVP CTR_DRBG vectors, AES-256 with the derivation function and prediction resistance off
+        let mut r = CtrDrbg::new(
+            &unhex::<32>("2d4c9f46b981c6a0b2b5d8c69391e569ff13851437ebc0fc00d616340252fed5"),
+            &unhex::<16>("0bf814b411f65ec4866be1abb59d3c32"),
+            &[],
+            false,
+        )
+        .unwrap();
+        r.reseed(
+            &unhex::<32>("93500fae4fa32b86033b7a7bac9d37e710dcc67ca266bc8607d665937766d207"),
+            &[],
+        )
+        .unwrap();
+        let mut out = [0; 64];
+        r.generate(&mut out, &[]).unwrap();
+        r.generate(&mut out, &[]).unwrap();
+        assert_eq!(out, unhex::<64>("322dd28670e75c0ea638f3cb68d6a9d6e50ddfd052b772a7b1d78263a7b8978b6740c2b65a9550c3a76325866fa97e16d74006bc96f26249b9f0a90d076f08e5"));
+
+        let mut r = CtrDrbg::new(
+            &unhex::<32>("a53e371017439193591e475087aaddd5c1c386cdca0ddb68e002d80fdc401a47"),
+            &unhex::<16>("a94da55afdc50ce51c9a3b8a4c448440"),
+            &unhex::<32>("8b52a24a93c34ea71e1ca705eb829ba65de4d4e07fa3d86b37845ff1c7d5f6d2"),
+            false,
+        )
+        .unwrap();
+        let mut out = [0; 16];
+        r.generate(
+            &mut out,
+            &unhex::<32>("20f422edf85ca16a01cfbe5f8d6c947fae12a857db2aa9bfc7b36581808d0d46"),
+        )
+        .unwrap();
+        r.reseed(
+            &unhex::<32>("dd40e5987b2716731568d276bf0c6715757903d3dede914642ddd467c879c81e"),
+            &unhex::<32>("7fd81fbd2ab51c115d834e99f65ca54020ed388ed59ee07593fe125e5d73fb75"),
+        )
+        .unwrap();
+        r.generate(
+            &mut out,
+            &unhex::<32>("cd2cff14693e4c9efdfe260de986004930bab1c65057772a62392c3b74ebc90d"),
+        )
+        .unwrap();
+        assert_eq!(out, unhex::<16>("4f78beb94d978ce9d097feadfafd355e"));
+    }
+
+    #[test]
+    fn test_cavp_prediction_resistance() {
+        // From the NIST CAVP CTR_DRBG vectors, AES-256 with the derivation function and prediction resistance on
+        let mut r = CtrDrbg::new(
+            &unhex::<32>("6168fc1af0b5956b85099b743f1378493b85ec93133ba94f96ab2ce4c88fdd6a"),
+            &unhex::<16>("add2bbbab76589c3216c55332b36ffa4"),
+            &unhex::<32>("6ecae72072d3845a32d34b2472c4632b9d12240c23268e8316370bd1064f686d"),
+            true,
+        )
+        .unwrap();
+        let mut out = [0; 16];
+        assert_eq!(r.generate(&mut out, &[]), Err(DrbgError::ReseedRequired));
+        r.generate_with_reseed(
+            &mut out,
+            &unhex::<32>("0b23afdff162d7d34397f87704a84220bdf60fc1172f9f54bb561786680ebaa9"),
+            &unhex::<32>("7e084abbe3217cc923d2f8b07398ba847423ab068ae222d37bce9bd24a76b8de"),
+        )
+        .unwrap();
+        r.generate_with_reseed(
+            &mut out,
+            &unhex::<32>("bf6c592a0d440fae9a5e0373d8a6e1cf25613824869e53e8a4df56f406079c0f"),
+            &unhex::<32>("946bc99fab8dc5ec71881d008c8968e4c8077736176d7978c7064e99042829c3"),
+        )
+        .unwrap();
+        assert_eq!(out, unhex::<16>("224ab4b8b6ee7db19ec9f9a0d9e29700"));
+    }
+}
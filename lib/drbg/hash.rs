@@ -0,0 +1,255 @@
+use super::sha256::Sha256;
+use crate::error::DrbgError;
+#[cfg(feature = "getrandom")]
+use crate::error::EntropyError;
+use crate::{CryptoRandom, RandomSource, SeedableRandom};
+
+/// The seed length for SHA-256, in bytes
+const SEED_LEN: usize = 55;
+
+/// Hash_DRBG Generator
+///
+/// The Hash_DRBG from NIST SP 800-90A, using SHA-256.
+/// It keeps a 440 bit value `V` and a constant `C`, and outputs hashes of `V`, `V + 1`, ...
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::HashDrbg;
+///
+/// // Instantiate, then generate with some additional input
+/// let mut r = HashDrbg::new(&[7; 32], &[8; 16], &[], false).unwrap();
+/// let mut buf = [0; 64];
+/// r.generate(&mut buf, b"request 1").unwrap();
+///
+/// // Mix in fresh entropy later
+/// r.reseed(&[9; 32], &[]).unwrap();
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct HashDrbg {
+    v: [u8; SEED_LEN],
+    c: [u8; SEED_LEN],
+    reseed_counter: u64,
+    prediction_resistance: bool,
+}
+
+impl HashDrbg {
+    fn instantiate_alg(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> HashDrbg {
+        let v = hash_df(&[entropy, nonce, personalization]);
+        HashDrbg {
+            v,
+            c: hash_df(&[&[0], &v]),
+            reseed_counter: 1,
+            prediction_resistance: false,
+        }
+    }
+
+    fn reseed_alg(&mut self, entropy: &[u8], additional: &[u8]) {
+        self.v = hash_df(&[&[1], &self.v, entropy, additional]);
+        self.c = hash_df(&[&[0], &self.v]);
+    }
+
+    fn generate_alg(&mut self, out: &mut [u8], additional: &[u8]) {
+        if !additional.is_empty() {
+            let w = Sha256::digest(&[&[2], &self.v, additional]);
+            add(&mut self.v, &w);
+        }
+
+        let mut data = self.v;
+        for chunk in out.chunks_mut(32) {
+            let w = Sha256::digest(&[&data]);
+            chunk.copy_from_slice(&w[..chunk.len()]);
+            add(&mut data, &[1]);
+        }
+
+        let h = Sha256::digest(&[&[3], &self.v]);
+        let c = self.c;
+        add(&mut self.v, &h);
+        add(&mut self.v, &c);
+        add(&mut self.v, &self.reseed_counter.to_be_bytes());
+    }
+}
+
+impl_drbg!(HashDrbg);
+
+/// The Hash_df derivation function, making a full seed from a list of byte strings
+fn hash_df(parts: &[&[u8]]) -> [u8; SEED_LEN] {
+    let bits = (SEED_LEN as u32 * 8).to_be_bytes();
+    let mut out = [0; SEED_LEN];
+    for (counter, chunk) in out.chunks_mut(32).enumerate() {
+        let mut h = Sha256::new();
+        h.update(&[counter as u8 + 1]);
+        h.update(&bits);
+        for i in parts {
+            h.update(i);
+        }
+        chunk.copy_from_slice(&h.finish()[..chunk.len()]);
+    }
+    out
+}
+
+/// Add a big endian number onto `x`, modulo `2^440`
+fn add(x: &mut [u8; SEED_LEN], y: &[u8]) {
+    let mut carry = 0;
+    let mut y = y.iter().rev();
+    for i in x.iter_mut().rev() {
+        let sum = *i as u16 + *y.next().unwrap_or(&0) as u16 + carry;
+        *i = sum as u8;
+        carry = sum >> 8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashDrbg;
+    use crate::drbg::unhex;
+    use crate::error::DrbgError;
+    use crate::{RandomSource, SeedableRandom};
+
+    #[test]
+    fn test_no_reseed() {
+        // From the NIST CAVP Hash_DRBG vectors, SHA-256 with no reseed
+        let mut r = HashDrbg::new(
+            &unhex::<32>("a65ad0f345db4e0effe875c3a2e71f42c7129d620ff5c119a9ef55f05185e0fb"),
+            &unhex::<16>("8581f9317517276e06e9607ddbcbcc2e"),
+            &[],
+            false,
+        )
+        .unwrap();
+        let mut out = [0; 128];
+        r.generate(&mut out, &[]).unwrap();
+        r.generate(&mut out, &[]).unwrap();
+        assert_eq!(out, unhex::<128>("d3e160c35b99f340b2628264d1751060e0045da383ff57a57d73a673d2b8d80daaf6a6c35a91bb4579d73fd0c8fed111b0391306828adfed528f018121b3febdc343e797b87dbb63db1333ded9d1ece177cfa6b71fe8ab1da46624ed6415e51ccde2c7ca86e283990eeaeb91120415528b2295910281b02dd431f4c9f70427df"));
+    }
+
+    // The next two take their inputs from the CAVP HMAC_DRBG vectors, with the outputs
+    // from OpenSSL's HASH-DRBG, which gives the CAVP output in `test_no_reseed`
+
+    #[test]
+    fn test_reseed() {
+        // With a personalization string and additional input
+        let mut r = HashDrbg::new(
+            &unhex::<32>("cdb0d9117cc6dbc9ef9dcb06a97579841d72dc18b2d46a1cb61e314012bdf416"),
+            &unhex::<16>("d0c0d01d156016d0eb6b7e9c7c3c8da8"),
+            &unhex::<32>("6f0fb9eab3f9ea7ab0a719bfa879bf0aaed683307fda0c6d73ce018b6e34faaa"),
+            false,
+        )
+        .unwrap();
+        r.reseed(
+            &unhex::<32>("8ec6f7d5a8e2e88f43986f70b86e050d07c84b931bcf18e601c5a3eee3064c82"),
+            &unhex::<32>("1ab4ca9014fa98a55938316de8ba5a68c629b0741bdd058c4d70c91cda5099b3"),
+        )
+        .unwrap();
+        let mut out = [0; 128];
+        r.generate(
+            &mut out,
+            &unhex::<32>("16e2d0721b58d839a122852abd3bf2c942a31c84d82fca74211871880d7162ff"),
+        )
+        .unwrap();
+        r.generate(
+            &mut out,
+            &unhex::<32>("53686f042a7b087d5d2eca0d2a96de131f275ed7151189f7ca52deaa78b79fb2"),
+        )
+        .unwrap();
+        assert_eq!(out, unhex::<128>("a6dfeb11081b4d7dc998d258f6e89bf7c3dc75bedd8b332199de4188c4776b54e664a2486eeba396fcee1145923464a10c802065a49e49b927740ea5ff01d6665e9c0f7c93b908200605dc9904bbd985fcdea32c9f3cfda67294d7671cee895e1a1b4e020a78f9df63f73ef825687da5eafc22d097129a8ddf22bc2ade08d8ea"));
+    }
+
+    #[test]
+    fn test_prediction_resistance() {
+        let mut r = HashDrbg::new(
+            &unhex::<32>("4294671d493dc085b5184607d7de2ff2b6aceb734a1b026f6cfee7c5a90f03da"),
+            &unhex::<16>("d071544e599235d5eb38b64b551d2a6e"),
+            &unhex::<32>("63bc769ae1d95a98bde870e4db7776297041d37c8a5c688d4e024b78d83f4d78"),
+            true,
+        )
+        .unwrap();
+        let mut out = [0; 128];
+        assert_eq!(r.generate(&mut out, &[]), Err(DrbgError::ReseedRequired));
+        r.generate_with_reseed(
+            &mut out,
+            &unhex::<32>("db9b4790b62336fbb9a684b82947065393eeef8f57bd2477141ad17e776dac34"),
+            &unhex::<32>("28848becd3f47696f124f4b14853a456156f69be583a7d4682cff8d44b39e1d3"),
+        )
+        .unwrap();
+        r.generate_with_reseed(
+            &mut out,
+            &unhex::<32>("4a9abe80f6f522f29878bedf8245b27940a76471006fb4a4110beb4decb6c341"),
+            &unhex::<32>("8bfce0b7132661c3cd78175d83926f643e36f7608eec2c5dac3ddcbacc8c2182"),
+        )
+        .unwrap();
+        assert_eq!(r.reseed_counter(), 2);
+        assert_eq!(out, unhex::<128>("b321865bcfa99a80e13a825cd9ea3b84d514c0c40eea7513ef049d624532c556008a6626c5fecbab15ec7b1a3191eb8e46119c817c1af03bf473a3209bb89537cf1747f7676e965b203b6807595695f5e3646c47d85765d4597135c84e9ed902fff11a6e46353ba7b70d69e47f5b7cf5a69a58d025de4fcc2f52862ba46471ac"));
+    }
+
+    #[test]
+    fn test_limits() {
+        assert_eq!(
+            HashDrbg::new(&[0; 31], &[0; 16], &[], false).err(),
+            Some(DrbgError::EntropyTooShort)
+        );
+
+        let mut r = HashDrbg::new(&[0; 32], &[0; 16], &[], false).unwrap();
+        assert_eq!(r.reseed_counter(), 1);
+        let mut out = [0; 1 << 16];
+        r.generate(&mut out, &[]).unwrap();
+        assert_eq!(r.reseed_counter(), 2);
+        let mut out = [0; (1 << 16) + 1];
+        assert_eq!(r.generate(&mut out, &[]), Err(DrbgError::RequestTooLarge));
+
+        // fill_bytes splits up large requests
+        r.fill_bytes(&mut out);
+        assert_eq!(r.reseed_counter(), 4);
+
+        r.reseed_counter = 1 << 48;
+        r.next_u32();
+        assert_eq!(
+            r.generate(&mut out[..1], &[]),
+            Err(DrbgError::ReseedRequired)
+        );
+        r.reseed(&[1; 32], &[]).unwrap();
+        assert_eq!(r.reseed_counter(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "getrandom")]
+    fn test_fill_bytes_reseeds() {
+        // Past the reseed interval, and on every request with prediction resistance,
+        // fill_bytes reseeds from the OS instead of failing
+        let mut r = HashDrbg::new(&[0; 32], &[0; 16], &[], false).unwrap();
+        let plain = r.clone();
+        r.reseed_counter = (1 << 48) + 1;
+        r.next_u64();
+        assert_eq!(r.reseed_counter(), 2);
+        assert_ne!(r, plain);
+
+        let mut a = HashDrbg::new(&[0; 32], &[0; 16], &[], true).unwrap();
+        let mut b = a.clone();
+        assert_ne!(a.next_u64(), b.next_u64());
+        let mut out = [0; (1 << 16) + 1];
+        a.fill_bytes(&mut out);
+        assert_eq!(a.reseed_counter(), 2);
+    }
+
+    #[test]
+    #[cfg(not(feature = "getrandom"))]
+    #[should_panic(expected = "generator must be reseeded")]
+    fn test_prediction_resistance_panic() {
+        HashDrbg::new(&[0; 32], &[0; 16], &[], true)
+            .unwrap()
+            .next_u64();
+    }
+
+    #[test]
+    fn test_add() {
+        let mut x = [0xff; 55];
+        super::add(&mut x, &[1]);
+        assert_eq!(x, [0; 55]);
+    }
+
+    #[test]
+    fn test_from_seed() {
+        let mut a = HashDrbg::from_seed([7; 32]);
+        let mut b = HashDrbg::new(&[7; 32], &[], &[], false).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+}
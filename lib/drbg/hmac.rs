@@ -0,0 +1,176 @@
+use super::sha256::hmac;
+use crate::error::DrbgError;
+#[cfg(feature = "getrandom")]
+use crate::error::EntropyError;
+use crate::{CryptoRandom, RandomSource, SeedableRandom};
+
+/// HMAC_DRBG Generator
+///
+/// The HMAC_DRBG from NIST SP 800-90A, using HMAC-SHA-256.
+/// It keeps a key `K` and a value `V`, and outputs `V` as it is rehashed under `K`.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::HmacDrbg;
+///
+/// // Instantiate, then generate with some additional input
+/// let mut r = HmacDrbg::new(&[7; 32], &[8; 16], &[], false).unwrap();
+/// let mut buf = [0; 64];
+/// r.generate(&mut buf, b"request 1").unwrap();
+///
+/// // Mix in fresh entropy later
+/// r.reseed(&[9; 32], &[]).unwrap();
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct HmacDrbg {
+    k: [u8; 32],
+    v: [u8; 32],
+    reseed_counter: u64,
+    prediction_resistance: bool,
+}
+
+impl HmacDrbg {
+    fn instantiate_alg(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> HmacDrbg {
+        let mut drbg = HmacDrbg {
+            k: [0; 32],
+            v: [1; 32],
+            reseed_counter: 1,
+            prediction_resistance: false,
+        };
+        drbg.update(&[entropy, nonce, personalization]);
+        drbg
+    }
+
+    fn reseed_alg(&mut self, entropy: &[u8], additional: &[u8]) {
+        self.update(&[entropy, additional]);
+    }
+
+    fn generate_alg(&mut self, out: &mut [u8], additional: &[u8]) {
+        if !additional.is_empty() {
+            self.update(&[additional]);
+        }
+
+        for chunk in out.chunks_mut(32) {
+            self.v = hmac(&self.k, &[&self.v]);
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+
+        self.update(&[additional]);
+    }
+
+    /// The HMAC_DRBG_Update function, mixing a list of byte strings into `K` and `V`
+    fn update(&mut self, provided: &[&[u8]]) {
+        let empty = provided.iter().all(|i| i.is_empty());
+        for round in 0..2 {
+            if round == 1 && empty {
+                return;
+            }
+
+            let mut parts: [&[u8]; 5] = [&self.v, &[round], &[], &[], &[]];
+            parts[2..2 + provided.len()].copy_from_slice(provided);
+            self.k = hmac(&self.k, &parts);
+            self.v = hmac(&self.k, &[&self.v]);
+        }
+    }
+}
+
+impl_drbg!(HmacDrbg);
+
+#[cfg(test)]
+mod tests {
+    use super::HmacDrbg;
+    use crate::drbg::unhex;
+    use crate::error::DrbgError;
+
+    #[test]
+    fn test_cavp_no_reseed() {
+        // From the NIST CAVP HMAC_DRBG vectors, SHA-256 with no reseed
+        let mut r = HmacDrbg::new(
+            &unhex::<32>("ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488"),
+            &unhex::<16>("659ba96c601dc69fc902940805ec0ca8"),
+            &[],
+            false,
+        )
+        .unwrap();
+        let mut out = [0; 128];
+        r.generate(&mut out, &[]).unwrap();
+        r.generate(&mut out, &[]).unwrap();
+        assert_eq!(out, unhex::<128>("e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc107694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8"));
+
+        let mut r = HmacDrbg::new(
+            &unhex::<32>("d3cc4d1acf3dde0c4bd2290d262337042dc632948223d3a2eaab87da44295fbd"),
+            &unhex::<16>("0109b0e729f457328aa18569a9224921"),
+            &[],
+            false,
+        )
+        .unwrap();
+        r.generate(
+            &mut out,
+            &unhex::<32>("3c311848183c9a212a26f27f8c6647e40375e466a0857cc39c4e47575d53f1f6"),
+        )
+        .unwrap();
+        r.generate(
+            &mut out,
+            &unhex::<32>("fcb9abd19ccfbccef88c9c39bfb3dd7b1c12266c9808992e305bc3cff566e4e4"),
+        )
+        .unwrap();
+        assert_eq!(out, unhex::<128>("9c7b758b212cd0fcecd5daa489821712e3cdea4467b560ef5ddc24ab47749a1f1ffdbbb118f4e62fcfca3371b8fbfc5b0646b83e06bfbbab5fac30ea09ea2bc76f1ea568c9be0444b2cc90517b20ca825f2d0eccd88e7175538b85d90ab390183ca6395535d34473af6b5a5b88f5a59ee7561573337ea819da0dcc3573a22974"));
+    }
+
+    #[test]
+    fn test_cavp_reseed() {
+        // From the NIST CAVP HMAC_DRBG vectors, SHA-256 with prediction resistance off,
+        // a personalization string and additional input
+        let mut r = HmacDrbg::new(
+            &unhex::<32>("cdb0d9117cc6dbc9ef9dcb06a97579841d72dc18b2d46a1cb61e314012bdf416"),
+            &unhex::<16>("d0c0d01d156016d0eb6b7e9c7c3c8da8"),
+            &unhex::<32>("6f0fb9eab3f9ea7ab0a719bfa879bf0aaed683307fda0c6d73ce018b6e34faaa"),
+            false,
+        )
+        .unwrap();
+        r.reseed(
+            &unhex::<32>("8ec6f7d5a8e2e88f43986f70b86e050d07c84b931bcf18e601c5a3eee3064c82"),
+            &unhex::<32>("1ab4ca9014fa98a55938316de8ba5a68c629b0741bdd058c4d70c91cda5099b3"),
+        )
+        .unwrap();
+        let mut out = [0; 128];
+        r.generate(
+            &mut out,
+            &unhex::<32>("16e2d0721b58d839a122852abd3bf2c942a31c84d82fca74211871880d7162ff"),
+        )
+        .unwrap();
+        r.generate(
+            &mut out,
+            &unhex::<32>("53686f042a7b087d5d2eca0d2a96de131f275ed7151189f7ca52deaa78b79fb2"),
+        )
+        .unwrap();
+        assert_eq!(out, unhex::<128>("dda04a2ca7b8147af1548f5d086591ca4fd951a345ce52b3cd49d47e84aa31a183e31fbc42a1ff1d95afec7143c8008c97bc2a9c091df0a763848391f68cb4a366ad89857ac725a53b303ddea767be8dc5f605b1b95f6d24c9f06be65a973a089320b3cc42569dcfd4b92b62a993785b0301b3fc452445656fce22664827b88f"));
+    }
+
+    #[test]
+    fn test_cavp_prediction_resistance() {
+        // From the NIST CAVP HMAC_DRBG vectors, SHA-256 with prediction resistance on
+        let mut r = HmacDrbg::new(
+            &unhex::<32>("4294671d493dc085b5184607d7de2ff2b6aceb734a1b026f6cfee7c5a90f03da"),
+            &unhex::<16>("d071544e599235d5eb38b64b551d2a6e"),
+            &unhex::<32>("63bc769ae1d95a98bde870e4db7776297041d37c8a5c688d4e024b78d83f4d78"),
+            true,
+        )
+        .unwrap();
+        let mut out = [0; 128];
+        assert_eq!(r.generate(&mut out, &[]), Err(DrbgError::ReseedRequired));
+        r.generate_with_reseed(
+            &mut out,
+            &unhex::<32>("db9b4790b62336fbb9a684b82947065393eeef8f57bd2477141ad17e776dac34"),
+            &unhex::<32>("28848becd3f47696f124f4b14853a456156f69be583a7d4682cff8d44b39e1d3"),
+        )
+        .unwrap();
+        r.generate_with_reseed(
+            &mut out,
+            &unhex::<32>("4a9abe80f6f522f29878bedf8245b27940a76471006fb4a4110beb4decb6c341"),
+            &unhex::<32>("8bfce0b7132661c3cd78175d83926f643e36f7608eec2c5dac3ddcbacc8c2182"),
+        )
+        .unwrap();
+        assert_eq!(out, unhex::<128>("e580dc969194b2b18a97478aef9d1a72390aff14562747bf080d741527a6655ce7fc135325b457483a9f9c70f91165a811cf4524b50d51199a0df3bd60d12abac27d0bf6618e6b114e05420352e23f3603dfe8a225dc19b3d1fff1dc245dc6b1df24c741744bec3f9437dbbf222df84881a457a589e7815ef132f686b760f012"));
+    }
+}
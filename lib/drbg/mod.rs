@@ -0,0 +1,251 @@
+//! Deterministic random bit generators from NIST SP 800-90A
+//!
+//! All three use a 256 bit security strength, and come with their own SHA-256 and AES-256.
+
+use crate::error::DrbgError;
+
+/// The security strength, in bytes
+const SECURITY_STRENGTH: usize = 32;
+
+/// The longest entropy input, nonce, personalization string or additional input, in bytes
+const MAX_LENGTH: u64 = 1 << 32;
+
+/// The most bytes that can be asked for in one request
+const MAX_REQUEST: usize = 1 << 16;
+
+/// The most requests between reseeds
+const RESEED_INTERVAL: u64 = 1 << 48;
+
+fn check_length(inputs: &[&[u8]]) -> Result<(), DrbgError> {
+    match inputs.iter().all(|i| i.len() as u64 <= MAX_LENGTH) {
+        true => Ok(()),
+        false => Err(DrbgError::InputTooLong),
+    }
+}
+
+fn check_entropy(entropy: &[u8]) -> Result<(), DrbgError> {
+    match entropy.len() >= SECURITY_STRENGTH {
+        true => Ok(()),
+        false => Err(DrbgError::EntropyTooShort),
+    }
+}
+
+/// Read a hex string into a byte array, for the test vectors
+#[cfg(test)]
+fn unhex<const N: usize>(s: &str) -> [u8; N] {
+    assert_eq!(s.len(), N * 2);
+    let mut out = [0; N];
+    for (i, j) in out.iter_mut().enumerate() {
+        *j = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).unwrap();
+    }
+    out
+}
+
+/// Define the SP 800-90A functions shared by all the DRBGs
+///
+/// Each DRBG implements the bare `instantiate_alg`, `reseed_alg` and `generate_alg`,
+/// and this wraps them with the input checks, the reseed counter and prediction resistance.
+/// It has to come before the modules that use it.
+macro_rules! impl_drbg {
+    ($name:ident) => {
+        impl $name {
+            #[doc = concat!("Instantiate a new ", stringify!($name))]
+            ///
+            /// The entropy input must be at least 32 bytes, and the nonce should have at least 16 bytes of entropy
+            /// (or never repeat). The personalization string can be empty.
+            ///
+            /// With `prediction_resistance` set, every request must come with fresh entropy through
+            /// `generate_with_reseed`, and `generate` returns [`DrbgError::ReseedRequired`].
+            /// [`RandomSource::fill_bytes`] gets the entropy from the OS itself.
+            /// ## Example
+            /// ```rust
+            /// // Import Lib
+            #[doc = concat!("use micro_rand::", stringify!($name), ";")]
+            ///
+            /// // Instantiate from entropy and a nonce from somewhere secure
+            #[doc = concat!("let mut r = ", stringify!($name), "::new(&[7; 32], &[8; 16], b\"my app\", false).unwrap();")]
+            ///
+            /// // Make a key
+            /// let mut key = [0; 32];
+            /// r.generate(&mut key, &[]).unwrap();
+            /// ```
+            pub fn new(
+                entropy: &[u8],
+                nonce: &[u8],
+                personalization: &[u8],
+                prediction_resistance: bool,
+            ) -> Result<$name, DrbgError> {
+                super::check_entropy(entropy)?;
+                super::check_length(&[entropy, nonce, personalization])?;
+
+                let mut drbg = $name::instantiate_alg(entropy, nonce, personalization);
+                drbg.prediction_resistance = prediction_resistance;
+                Ok(drbg)
+            }
+
+            /// Reseed with fresh entropy and optional additional input
+            ///
+            /// This resets the reseed counter.
+            pub fn reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<(), DrbgError> {
+                super::check_entropy(entropy)?;
+                super::check_length(&[entropy, additional])?;
+
+                self.reseed_alg(entropy, additional);
+                self.reseed_counter = 1;
+                Ok(())
+            }
+
+            /// Fill a buffer with random bytes, with optional additional input
+            ///
+            /// At most `2^16` bytes can be asked for at once.
+            /// Returns [`DrbgError::ReseedRequired`] if prediction resistance is on
+            /// or if there have been `2^48` requests since the last reseed.
+            pub fn generate(&mut self, out: &mut [u8], additional: &[u8]) -> Result<(), DrbgError> {
+                if out.len() > super::MAX_REQUEST {
+                    return Err(DrbgError::RequestTooLarge);
+                }
+                super::check_length(&[additional])?;
+                if self.prediction_resistance || self.reseed_counter > super::RESEED_INTERVAL {
+                    return Err(DrbgError::ReseedRequired);
+                }
+
+                self.generate_alg(out, additional);
+                self.reseed_counter += 1;
+                Ok(())
+            }
+
+            /// Reseed with fresh entropy, then fill a buffer with random bytes
+            ///
+            /// This is a request with prediction resistance, so the additional input
+            /// goes into the reseed.
+            pub fn generate_with_reseed(
+                &mut self,
+                out: &mut [u8],
+                entropy: &[u8],
+                additional: &[u8],
+            ) -> Result<(), DrbgError> {
+                if out.len() > super::MAX_REQUEST {
+                    return Err(DrbgError::RequestTooLarge);
+                }
+                self.reseed(entropy, additional)?;
+
+                self.generate_alg(out, &[]);
+                self.reseed_counter += 1;
+                Ok(())
+            }
+
+            /// Get the number of requests since the last reseed, plus one
+            pub fn reseed_counter(&self) -> u64 {
+                self.reseed_counter
+            }
+
+            /// Check if every request needs fresh entropy
+            pub fn prediction_resistance(&self) -> bool {
+                self.prediction_resistance
+            }
+
+            /// Reseed with 32 bytes of entropy from the OS, for `fill_bytes`
+            fn reseed_from_os(&mut self) {
+                #[cfg(feature = "getrandom")]
+                {
+                    let mut entropy = [0; super::SECURITY_STRENGTH];
+                    match crate::fill_entropy(&mut entropy) {
+                        Ok(()) => self.reseed_with(entropy),
+                        Err(e) => panic!("could not reseed the generator: {}", e),
+                    }
+                }
+                #[cfg(not(feature = "getrandom"))]
+                panic!("generator must be reseeded");
+            }
+        }
+
+        impl RandomSource for $name {
+            /// The first 4 bytes from [`RandomSource::fill_bytes`], as a little endian `u32`
+            fn next_u32(&mut self) -> u32 {
+                let mut bytes = [0; 4];
+                self.fill_bytes(&mut bytes);
+                u32::from_le_bytes(bytes)
+            }
+
+            /// The first 8 bytes from [`RandomSource::fill_bytes`], as a little endian `u64`
+            fn next_u64(&mut self) -> u64 {
+                let mut bytes = [0; 8];
+                self.fill_bytes(&mut bytes);
+                u64::from_le_bytes(bytes)
+            }
+
+            /// Generate bytes with no additional input, `2^16` at a time
+            ///
+            /// When a request needs fresh entropy (every request with prediction resistance on,
+            /// or the first after `2^48` requests) the generator reseeds itself from the OS first.
+            ///
+            /// ## Panics
+            /// If the OS can't give any entropy, or without the `getrandom` feature
+            /// as there is nowhere to get it from.
+            #[doc = concat!("Use [`", stringify!($name), "::generate_with_reseed`] to bring your own entropy.")]
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                for chunk in dest.chunks_mut(super::MAX_REQUEST) {
+                    if self.prediction_resistance || self.reseed_counter > super::RESEED_INTERVAL {
+                        self.reseed_from_os();
+                    }
+
+                    self.generate_alg(chunk, &[]);
+                    self.reseed_counter += 1;
+                }
+            }
+        }
+
+        impl SeedableRandom for $name {
+            type Seed = [u8; 32];
+
+            /// The seed is the entropy input, with an empty nonce and personalization string
+            ///
+            /// This is only for repeatable output, like in tests:
+            /// anyone who can guess the seed can work out every output.
+            fn from_seed(seed: [u8; 32]) -> $name {
+                $name::instantiate_alg(&seed, &[], &[])
+            }
+
+            /// Instantiate with 32 bytes of entropy and a 16 byte nonce from the OS
+            ///
+            /// Prediction resistance is off, and the personalization string is empty.
+            /// Use `new` with entropy from [`fill_entropy`](crate::fill_entropy) for anything else.
+            #[cfg(feature = "getrandom")]
+            fn from_entropy() -> Result<$name, EntropyError> {
+                let mut entropy = [0; 32];
+                let mut nonce = [0; 16];
+                crate::fill_entropy(&mut entropy)?;
+                crate::fill_entropy(&mut nonce)?;
+                Ok($name::instantiate_alg(&entropy, &nonce, &[]))
+            }
+
+            /// Runs the SP 800-90A reseed, with no additional input
+            fn reseed_with(&mut self, entropy: [u8; 32]) {
+                self.reseed_alg(&entropy, &[]);
+                self.reseed_counter = 1;
+            }
+        }
+
+        impl CryptoRandom for $name {}
+
+        /// Shows the reseed counter and prediction resistance, but not the secret state
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("reseed_counter", &self.reseed_counter)
+                    .field("prediction_resistance", &self.prediction_resistance)
+                    .finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+mod aes;
+mod ctr;
+mod hash;
+mod hmac;
+mod sha256;
+
+pub use ctr::CtrDrbg;
+pub use hash::HashDrbg;
+pub use hmac::HmacDrbg;
@@ -0,0 +1,191 @@
+use core::convert::TryInto;
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// SHA-256 hash, fed in pieces
+pub(crate) struct Sha256 {
+    state: [u32; 8],
+    block: [u8; 64],
+    len: u64,
+}
+
+impl Sha256 {
+    pub(crate) fn new() -> Sha256 {
+        Sha256 {
+            state: H0,
+            block: [0; 64],
+            len: 0,
+        }
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let used = (self.len % 64) as usize;
+            let take = (64 - used).min(data.len());
+            self.block[used..used + take].copy_from_slice(&data[..take]);
+            self.len += take as u64;
+            data = &data[take..];
+
+            if used + take == 64 {
+                self.compress();
+            }
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> [u8; 32] {
+        let bits = self.len * 8;
+        self.update(&[0x80]);
+        while self.len % 64 != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bits.to_be_bytes());
+
+        let mut out = [0; 32];
+        for (i, j) in out.chunks_exact_mut(4).zip(self.state.iter()) {
+            i.copy_from_slice(&j.to_be_bytes());
+        }
+        out
+    }
+
+    /// Hash a list of byte strings, one after another
+    pub(crate) fn digest(parts: &[&[u8]]) -> [u8; 32] {
+        let mut h = Sha256::new();
+        for i in parts {
+            h.update(i);
+        }
+        h.finish()
+    }
+
+    fn compress(&mut self) {
+        let mut w = [0; 64];
+        for (i, j) in w.iter_mut().zip(self.block.chunks_exact(4)) {
+            *i = u32::from_be_bytes(j.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ w[i - 15] >> 3;
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ w[i - 2] >> 10;
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (i, j) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h].iter()) {
+            *i = i.wrapping_add(*j);
+        }
+    }
+}
+
+/// HMAC-SHA-256 of a list of byte strings, one after another
+///
+/// The key is always 32 bytes here, so it never needs hashing first.
+pub(crate) fn hmac(key: &[u8; 32], parts: &[&[u8]]) -> [u8; 32] {
+    let mut pad = [0x36; 64];
+    for (i, j) in pad.iter_mut().zip(key.iter()) {
+        *i ^= j;
+    }
+    let mut inner = Sha256::new();
+    inner.update(&pad);
+    for i in parts {
+        inner.update(i);
+    }
+    let inner = inner.finish();
+
+    for i in pad.iter_mut() {
+        *i ^= 0x36 ^ 0x5c;
+    }
+    Sha256::digest(&[&pad, &inner])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hmac, Sha256};
+
+    #[test]
+    fn test_sha256() {
+        // From FIPS 180-2, appendix B
+        assert_eq!(
+            Sha256::digest(&[b"abc"]),
+            [
+                0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae,
+                0x22, 0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61,
+                0xf2, 0x00, 0x15, 0xad
+            ]
+        );
+        assert_eq!(
+            Sha256::digest(&[
+                b"abcdbcdecdefdefgefghfghighijhijk",
+                b"ijkljklmklmnlmnomnopnopq"
+            ]),
+            [
+                0x24, 0x8d, 0x6a, 0x61, 0xd2, 0x06, 0x38, 0xb8, 0xe5, 0xc0, 0x26, 0x93, 0x0c, 0x3e,
+                0x60, 0x39, 0xa3, 0x3c, 0xe4, 0x59, 0x64, 0xff, 0x21, 0x67, 0xf6, 0xec, 0xed, 0xd4,
+                0x19, 0xdb, 0x06, 0xc1
+            ]
+        );
+
+        let mut h = Sha256::new();
+        for _ in 0..1_000_000 / 40 {
+            h.update(b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+        }
+        assert_eq!(
+            h.finish(),
+            [
+                0xcd, 0xc7, 0x6e, 0x5c, 0x99, 0x14, 0xfb, 0x92, 0x81, 0xa1, 0xc7, 0xe2, 0x84, 0xd7,
+                0x3e, 0x67, 0xf1, 0x80, 0x9a, 0x48, 0xa4, 0x97, 0x20, 0x0e, 0x04, 0x6d, 0x39, 0xcc,
+                0xc7, 0x11, 0x2c, 0xd0
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hmac() {
+        // RFC 4231 test case 2, with the key padded out to 32 bytes
+        let mut key = [0; 32];
+        key[..4].copy_from_slice(b"Jefe");
+        assert_eq!(
+            hmac(&key, &[b"what do ya want ", b"for nothing?"]),
+            [
+                0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95,
+                0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9,
+                0x64, 0xec, 0x38, 0x43
+            ]
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use crate::error::EntropyError;
+
+/// A source of fresh entropy
+///
+/// Unlike a [`RandomSource`](crate::RandomSource), every byte should be unpredictable,
+/// like the OS entropy pool or a hardware random number generator.
+/// This is what [`Reseeding`](crate::Reseeding) takes new seeds from.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{EntropyError, EntropySource};
+///
+/// // A hardware generator, read through some register
+/// struct Trng;
+///
+/// impl EntropySource for Trng {
+///     fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+///         for i in dest.iter_mut() {
+///             *i = 0x5a; // read a byte here
+///         }
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait EntropySource {
+    /// Fill a buffer with entropy
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), EntropyError>;
+}
+
+impl<E: EntropySource + ?Sized> EntropySource for &mut E {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        (**self).fill_entropy(dest)
+    }
+}
+
+/// Fill a buffer with entropy from the OS
+///
+/// Uses the `getrandom` crate, which calls `getrandom` on Linux (falling back to `/dev/urandom`),
+/// `BCryptGenRandom` on Windows and the closest equivalent elsewhere.
+/// Needs the `getrandom` feature.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::fill_entropy;
+///
+/// // Get a 32 byte key
+/// let mut key = [0; 32];
+/// fill_entropy(&mut key).unwrap();
+/// ```
+#[cfg(feature = "getrandom")]
+pub fn fill_entropy(buf: &mut [u8]) -> Result<(), EntropyError> {
+    getrandom::getrandom(buf)?;
+    Ok(())
+}
+
+/// The OS entropy source
+///
+/// An [`EntropySource`] that reads with [`fill_entropy`].
+/// Needs the `getrandom` feature.
+#[cfg(feature = "getrandom")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsEntropy;
+
+#[cfg(feature = "getrandom")]
+impl EntropySource for OsEntropy {
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        fill_entropy(dest)
+    }
+}
+
+#[cfg(all(test, feature = "getrandom"))]
+mod tests {
+    use super::{fill_entropy, EntropySource, OsEntropy};
+    #[cfg(feature = "crypto")]
+    use crate::{CtrDrbg, HashDrbg, HmacDrbg};
+    use crate::{Lcg, Lcg128, RandomSource, SeedableRandom, Xoshiro256StarStar};
+
+    #[test]
+    fn test_fill_entropy() {
+        let mut a = [0; 32];
+        let mut b = [0; 32];
+        fill_entropy(&mut a).unwrap();
+        OsEntropy.fill_entropy(&mut b).unwrap();
+        assert_ne!(a, [0; 32]);
+        assert_ne!(a, b);
+        fill_entropy(&mut []).unwrap();
+    }
+
+    #[test]
+    fn test_from_entropy() {
+        let mut a = Xoshiro256StarStar::from_entropy().unwrap();
+        let mut b = Xoshiro256StarStar::from_entropy().unwrap();
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn test_from_entropy_lcg() {
+        let a = Lcg128::from_entropy().unwrap();
+        let b = Lcg128::from_entropy().unwrap();
+        assert_ne!(a.state(), b.state());
+        assert_ne!(Lcg::from_entropy().unwrap(), Lcg::from_entropy().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "crypto")]
+    fn test_from_entropy_drbg() {
+        let mut a = HashDrbg::from_entropy().unwrap();
+        let mut b = HmacDrbg::from_entropy().unwrap();
+        let mut c = CtrDrbg::from_entropy().unwrap();
+        assert!(!a.prediction_resistance());
+        assert_eq!(c.reseed_counter(), 1);
+        assert_ne!(a.next_u64(), HashDrbg::from_entropy().unwrap().next_u64());
+        assert_ne!(b.next_u64(), c.next_u64());
+    }
+}
@@ -0,0 +1,156 @@
+use core::fmt;
+
+/// Reasons a set of generator parameters can be rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamError {
+    /// The modulus `m` is zero
+    ZeroModulus,
+
+    /// The modulus `m` is negative
+    NegativeModulus,
+
+    /// The multiplier `a` is not in `[1, m)`
+    MultiplierOutOfRange,
+
+    /// The increment `c` is not in `[0, m)`
+    IncrementOutOfRange,
+
+    /// The seed is not in `[0, m)`
+    SeedOutOfRange,
+
+    /// The seed maps to itself, so the generator would never change
+    FixedPointSeed,
+
+    /// The parameters do not give the longest possible period
+    NotFullPeriod,
+
+    /// The modulus is not a power of two that fits in the state
+    ModulusNotPowerOfTwo,
+
+    /// The output bits are not inside the state, or there are more than 64 of them
+    OutputOutOfRange,
+
+    /// The multiplier `a` has no inverse mod `m`, so the generator can't be stepped back
+    MultiplierNotInvertible,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParamError::ZeroModulus => "modulus is zero",
+            ParamError::NegativeModulus => "modulus is negative",
+            ParamError::MultiplierOutOfRange => "multiplier is not in [1, m)",
+            ParamError::IncrementOutOfRange => "increment is not in [0, m)",
+            ParamError::SeedOutOfRange => "seed is not in [0, m)",
+            ParamError::FixedPointSeed => "seed is a fixed point of the generator",
+            ParamError::NotFullPeriod => "parameters do not give a full period",
+            ParamError::ModulusNotPowerOfTwo => "modulus is not a power of two that fits the state",
+            ParamError::OutputOutOfRange => "output bits do not fit in the state",
+            ParamError::MultiplierNotInvertible => "multiplier has no inverse mod m",
+        })
+    }
+}
+
+/// Reasons a deterministic random bit generator can refuse a request
+#[cfg(feature = "crypto")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrbgError {
+    /// The entropy input is shorter than the 256 bit security strength
+    EntropyTooShort,
+
+    /// The entropy input, nonce, personalization string or additional input is longer than `2^32` bytes
+    InputTooLong,
+
+    /// More than `2^16` bytes were asked for in one request
+    RequestTooLarge,
+
+    /// The generator must be reseeded before it can generate more,
+    /// either because the reseed counter ran out or because prediction resistance is on
+    ReseedRequired,
+}
+
+#[cfg(feature = "crypto")]
+impl fmt::Display for DrbgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DrbgError::EntropyTooShort => "entropy input is shorter than the security strength",
+            DrbgError::InputTooLong => "input is longer than 2^32 bytes",
+            DrbgError::RequestTooLarge => "request is larger than 2^16 bytes",
+            DrbgError::ReseedRequired => "generator must be reseeded",
+        })
+    }
+}
+
+/// Reasons a saved generator state can be rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// The bytes don't start with the `MRNG` magic, so they are not a saved state
+    BadMagic,
+
+    /// The state was saved in a newer format version than this one knows
+    UnsupportedVersion(u8),
+
+    /// The state was saved by a different algorithm, with this tag
+    WrongAlgorithm(u8),
+
+    /// The buffer is not the right length for the algorithm
+    WrongLength,
+
+    /// The checksum does not match, so the bytes were changed after saving
+    ChecksumMismatch,
+
+    /// The saved parameters can't be run, like a modulus of 0
+    InvalidState,
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::BadMagic => f.write_str("not a saved generator state"),
+            StateError::UnsupportedVersion(i) => {
+                write!(f, "unsupported state format version {}", i)
+            }
+            StateError::WrongAlgorithm(i) => {
+                write!(f, "state is from a different algorithm (tag {})", i)
+            }
+            StateError::WrongLength => f.write_str("state has the wrong length"),
+            StateError::ChecksumMismatch => f.write_str("state checksum does not match"),
+            StateError::InvalidState => f.write_str("state parameters are invalid"),
+        }
+    }
+}
+
+/// Reasons an entropy source could not give any entropy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntropyError {
+    /// There is no known entropy source on this target
+    Unsupported,
+
+    /// An OS call failed, with this error code (`errno` on Unix)
+    Os(i32),
+
+    /// The entropy source could not be used for some other reason,
+    /// like `/dev/urandom` not being readable
+    Unavailable,
+}
+
+impl fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntropyError::Unsupported => f.write_str("no entropy source on this target"),
+            EntropyError::Os(i) => write!(f, "entropy source failed with OS error {}", i),
+            EntropyError::Unavailable => f.write_str("entropy source is unavailable"),
+        }
+    }
+}
+
+#[cfg(feature = "getrandom")]
+impl From<getrandom::Error> for EntropyError {
+    fn from(err: getrandom::Error) -> EntropyError {
+        match err.raw_os_error() {
+            Some(i) => EntropyError::Os(i),
+            None if err == getrandom::Error::UNSUPPORTED => EntropyError::Unsupported,
+            None => EntropyError::Unavailable,
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use crate::RandomSource;
+
+/// Define a method to get an integer within an inclusive range
+///
+/// The span of the range is computed in the unsigned type, widened to the sampler's type.
+/// The full range wraps around to a span of 0, which the samplers treat as every value.
+macro_rules! next_int {
+    ($name:ident, $t:ty, $unsigned:ty, $wide:ty, $below:ident) => {
+        #[doc = concat!("Get the next ", stringify!($t), " from a generator within a range")]
+        ///
+        /// The result is uniformly distributed over `[min, max]`, both ends included.
+        /// ## Panics
+        /// If `min` is greater than `max`
+        /// ## Example
+        /// ```rust
+        /// // Import Lib
+        /// use micro_rand::{Random, RandomExt};
+        ///
+        /// // Make a new, custom random generator
+        /// let mut r = Random::new(1234);
+        ///
+        #[doc = concat!("// Get a random ", stringify!($t), " between 0 and 100")]
+        #[doc = concat!("let f = r.", stringify!($name), "(0, 100);")]
+        /// assert!(f <= 100);
+        /// ```
+        fn $name(&mut self, min: $t, max: $t) -> $t {
+            assert!(min <= max, "min must not be greater than max");
+            let span = (max.wrapping_sub(min) as $unsigned as $wide).wrapping_add(1);
+            min.wrapping_add($below(self, span) as $t)
+        }
+    };
+}
+
+/// Convenience methods for every [`RandomSource`]
+///
+/// Everything here is built on [`RandomSource::next_u32`] and [`RandomSource::next_u64`],
+/// so it works the same way for every generator.
+pub trait RandomExt: RandomSource {
+    /// Get the next u128 from a generator
+    ///
+    /// Made from two [`RandomSource::next_u64`] calls, high bits first.
+    fn next_u128(&mut self) -> u128 {
+        (self.next_u64() as u128) << 64 | self.next_u64() as u128
+    }
+
+    /// Get the next usize from a generator
+    ///
+    /// Same as [`RandomSource::next_u64`] on 64 bit targets and [`RandomSource::next_u32`] on smaller ones.
+    fn next_usize(&mut self) -> usize {
+        #[cfg(target_pointer_width = "64")]
+        return self.next_u64() as usize;
+        #[cfg(not(target_pointer_width = "64"))]
+        return self.next_u32() as usize;
+    }
+
+    /// Get the next i32 from a generator
+    ///
+    /// The bits of [`RandomSource::next_u32`].
+    fn next_i32(&mut self) -> i32 {
+        self.next_u32() as i32
+    }
+
+    /// Get the next i64 from a generator
+    ///
+    /// The bits of [`RandomSource::next_u64`].
+    fn next_i64(&mut self) -> i64 {
+        self.next_u64() as i64
+    }
+
+    /// Get the next i128 from a generator
+    ///
+    /// The bits of [`RandomExt::next_u128`].
+    fn next_i128(&mut self) -> i128 {
+        self.next_u128() as i128
+    }
+
+    /// Get the next isize from a generator
+    ///
+    /// The bits of [`RandomExt::next_usize`].
+    fn next_isize(&mut self) -> isize {
+        self.next_usize() as isize
+    }
+
+    next_int!(next_int_i8, i8, u8, u32, below_u32);
+    next_int!(next_int_i16, i16, u16, u32, below_u32);
+    next_int!(next_int_i32, i32, u32, u32, below_u32);
+    next_int!(next_int_i64, i64, u64, u64, below_u64);
+    next_int!(next_int_i128, i128, u128, u128, below_u128);
+    next_int!(next_int_isize, isize, usize, u64, below_u64);
+    next_int!(next_int_u8, u8, u8, u32, below_u32);
+    next_int!(next_int_u16, u16, u16, u32, below_u32);
+    next_int!(next_int_u32, u32, u32, u32, below_u32);
+    next_int!(next_int_u64, u64, u64, u64, below_u64);
+    next_int!(next_int_u128, u128, u128, u128, below_u128);
+    next_int!(next_int_usize, usize, usize, u64, below_u64);
+
+    /// Get the next bool from a generator
+    ///
+    /// Uses the top bit of [`RandomSource::next_u32`].
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Random, RandomExt};
+    ///
+    /// // Make a new random generator
+    /// let mut r = Random::new(1234);
+    ///
+    /// // Flip a coin
+    /// let heads = r.next_bool();
+    /// ```
+    fn next_bool(&mut self) -> bool {
+        self.next_u32() >> 31 == 1
+    }
+
+    /// Shuffle a slice in place
+    ///
+    /// Uses the Fisher–Yates shuffle, so every order is equally likely.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Random, RandomExt};
+    ///
+    /// // Make a new random generator
+    /// let mut r = Random::new(1234);
+    ///
+    /// // Shuffle a deck
+    /// let mut deck = [1, 2, 3, 4, 5];
+    /// r.shuffle(&mut deck);
+    /// ```
+    fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.next_int_usize(0, i);
+            slice.swap(i, j);
+        }
+    }
+
+    /// Pick a random item from a slice
+    ///
+    /// Returns `None` if the slice is empty.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Random, RandomExt};
+    ///
+    /// // Make a new random generator
+    /// let mut r = Random::new(1234);
+    ///
+    /// // Pick a color
+    /// let color = r.choose(&["red", "green", "blue"]);
+    /// assert!(color.is_some());
+    /// ```
+    fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        match slice.len() {
+            0 => None,
+            len => slice.get(self.next_int_usize(0, len - 1)),
+        }
+    }
+}
+
+impl<R: RandomSource + ?Sized> RandomExt for R {}
+
+/// Get a uniform integer in `[0, span)`, where a span of 0 means `2^32`
+///
+/// Uses Lemire's multiply and reject method.
+fn below_u32<R: RandomSource + ?Sized>(r: &mut R, span: u32) -> u32 {
+    if span == 0 {
+        return r.next_u32();
+    }
+
+    let mut x = r.next_u32() as u64 * span as u64;
+    if (x as u32) < span {
+        let t = span.wrapping_neg() % span;
+        while (x as u32) < t {
+            x = r.next_u32() as u64 * span as u64;
+        }
+    }
+
+    (x >> 32) as u32
+}
+
+/// Get a uniform integer in `[0, span)`, where a span of 0 means `2^64`
+///
+/// Uses Lemire's multiply and reject method.
+fn below_u64<R: RandomSource + ?Sized>(r: &mut R, span: u64) -> u64 {
+    if span == 0 {
+        return r.next_u64();
+    }
+
+    let mut x = r.next_u64() as u128 * span as u128;
+    if (x as u64) < span {
+        let t = span.wrapping_neg() % span;
+        while (x as u64) < t {
+            x = r.next_u64() as u128 * span as u128;
+        }
+    }
+
+    (x >> 64) as u64
+}
+
+/// Get a uniform integer in `[0, span)`, where a span of 0 means `2^128`
+///
+/// Masks off the bits above the span and rejects anything still out of range.
+fn below_u128<R: RandomSource + ?Sized>(r: &mut R, span: u128) -> u128 {
+    let mask = match span {
+        0 => u128::MAX,
+        _ => u128::MAX
+            .checked_shr((span - 1).leading_zeros())
+            .unwrap_or(0),
+    };
+
+    loop {
+        let x = r.next_u128() & mask;
+        if span == 0 || x < span {
+            return x;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RandomExt;
+    use crate::Random;
+
+    #[test]
+    fn test_next_int_full_range() {
+        let mut r = Random::new(1234);
+        for _ in 0..1_000 {
+            r.next_int_i64(i64::MIN, i64::MAX);
+            r.next_int_u64(0, u64::MAX);
+            r.next_int_i128(i128::MIN, i128::MAX);
+            r.next_int_u128(0, u128::MAX);
+            assert_eq!(r.next_int_i64(i64::MAX, i64::MAX), i64::MAX);
+            assert_eq!(r.next_int_i128(i128::MIN, i128::MIN), i128::MIN);
+        }
+
+        let mut r = Random::custom_new(1, 86_284, 2, 7_263_957_720);
+        for _ in 0..1_000 {
+            let i = r.next_int_i64(-5, 5);
+            assert!((-5..=5).contains(&i));
+        }
+    }
+
+    #[test]
+    fn test_next_int_u8_exhaustive() {
+        let mut r = Random::new(1234);
+        for min in 0..=u8::MAX {
+            for max in min..=u8::MAX {
+                let i = r.next_int_u8(min, max);
+                assert!(i >= min && i <= max);
+            }
+        }
+
+        let mut seen = [0_u32; 256];
+        for _ in 0..256 * 200 {
+            seen[r.next_int_u8(0, u8::MAX) as usize] += 1;
+        }
+        assert!(seen.iter().all(|&i| i > 100 && i < 300));
+    }
+
+    #[test]
+    fn test_next_int_i8_exhaustive() {
+        let mut r = Random::new(4321);
+        for min in i8::MIN..=i8::MAX {
+            for max in min..=i8::MAX {
+                let i = r.next_int_i8(min, max);
+                assert!(i >= min && i <= max);
+            }
+        }
+
+        let ranges = [
+            (i8::MIN, i8::MAX),
+            (-3, 3),
+            (i8::MIN, i8::MIN + 1),
+            (126, 127),
+        ];
+        for (min, max) in ranges.iter() {
+            let span = (*max as i32 - *min as i32 + 1) as usize;
+            let mut seen = [false; 256];
+            for _ in 0..span * 64 {
+                let i = r.next_int_i8(*min, *max);
+                assert!(i >= *min && i <= *max);
+                seen[(i as i32 - *min as i32) as usize] = true;
+            }
+            assert!(seen[..span].iter().all(|&i| i));
+        }
+    }
+
+    #[test]
+    fn test_next_int_uniform() {
+        let mut r = Random::custom_new(0, 43, 1, 117_649);
+        assert!(r.has_full_period());
+
+        let mut seen = [0_u32; 3];
+        for _ in 0..30_000 {
+            seen[r.next_int_u8(0, 2) as usize] += 1;
+        }
+        assert!(seen.iter().all(|&i| i > 9_500 && i < 10_500));
+    }
+
+    #[test]
+    fn test_next_raw_bits() {
+        // Every bit should be set about half the time
+        let mut r = Random::new(1234);
+        let mut ones = [0_u32; 128];
+        for _ in 0..10_000 {
+            let x = r.next_u128();
+            for (i, one) in ones.iter_mut().enumerate() {
+                *one += (x >> i) as u32 & 1;
+            }
+        }
+        assert!(ones.iter().all(|&i| i > 4_700 && i < 5_300));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_next_int_bad_range() {
+        Random::new(1234).next_int_u8(10, 5);
+    }
+
+    #[test]
+    fn test_next_bool() {
+        let mut r = Random::new(1234);
+        let heads = (0..10_000).filter(|_| r.next_bool()).count();
+        assert!(heads > 4_800 && heads < 5_200);
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut r = Random::new(1234);
+
+        // Every order of 3 items should come up about as often
+        let mut seen = [0_u32; 6];
+        for _ in 0..6_000 {
+            let mut items = [0, 1, 2];
+            r.shuffle(&mut items);
+            let i = match items {
+                [0, 1, 2] => 0,
+                [0, 2, 1] => 1,
+                [1, 0, 2] => 2,
+                [1, 2, 0] => 3,
+                [2, 0, 1] => 4,
+                _ => 5,
+            };
+            seen[i] += 1;
+        }
+        assert!(seen.iter().all(|&i| i > 900 && i < 1_100));
+
+        let mut empty: [u8; 0] = [];
+        r.shuffle(&mut empty);
+    }
+
+    #[test]
+    fn test_choose() {
+        let mut r = Random::new(1234);
+        let empty: [u8; 0] = [];
+        assert_eq!(r.choose(&empty), None);
+        assert_eq!(r.choose(&[7]), Some(&7));
+
+        let items = [1, 2, 3];
+        for _ in 0..100 {
+            assert!(items.contains(r.choose(&items).unwrap()));
+        }
+    }
+}
@@ -0,0 +1,1136 @@
+use crate::error::ParamError;
+use crate::{RandomSource, SeedableRandom, SplitRandom};
+
+/// How a preset turns a user seed into the first state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Seeding {
+    /// The seed mod `m`
+    Plain,
+
+    /// The seed mod `m`, with 0 replaced by 1, like C++ `linear_congruential_engine`
+    Minstd,
+
+    /// The low 32 bits of the seed, with 0 replaced by 1, like glibc `srandom`
+    Glibc,
+
+    /// The seed xored with the multiplier, like `java.util.Random`
+    Java,
+
+    /// The low 32 bits of the seed above `0x330e`, like POSIX `srand48`
+    Drand48,
+}
+
+/// A named set of LCG parameters from a historical platform
+///
+/// Each preset has the step `state = (a * state + c) mod m`,
+/// the bits of the state that the platform hands out (`bits` wide, starting at bit `shift`),
+/// and the way the platform seeds it. Run one with [`Lcg::from_preset`].
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{Lcg, LcgPreset};
+///
+/// // Get the same numbers as MSVC rand() after srand(1)
+/// let mut r = Lcg::from_preset(&LcgPreset::MSVC, 1);
+/// assert_eq!(r.next_output(), 41);
+/// assert_eq!(r.next_output(), 18467);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LcgPreset {
+    /// The name of the platform or generator
+    pub name: &'static str,
+
+    /// The multiplier
+    pub a: u64,
+
+    /// The increment
+    pub c: u64,
+
+    /// The modulus
+    pub m: u128,
+
+    /// The lowest bit of the state in the output
+    pub shift: u32,
+
+    /// The number of bits in the output
+    pub bits: u32,
+
+    /// The generator is known to fail basic statistical tests
+    ///
+    /// Only use these to reproduce old results.
+    pub broken: bool,
+
+    seeding: Seeding,
+}
+
+impl LcgPreset {
+    /// Park and Miller's minimal standard, C++ `std::minstd_rand0`
+    ///
+    /// This is also what [`Random::new`](crate::Random::new) uses.
+    pub const MINSTD_RAND0: LcgPreset = LcgPreset {
+        name: "minstd_rand0",
+        a: 16_807,
+        c: 0,
+        m: 2_147_483_647,
+        shift: 0,
+        bits: 31,
+        broken: false,
+        seeding: Seeding::Minstd,
+    };
+
+    /// Park, Miller and Stockmeyer's revised minimal standard, C++ `std::minstd_rand`
+    pub const MINSTD_RAND: LcgPreset = LcgPreset {
+        name: "minstd_rand",
+        a: 48_271,
+        c: 0,
+        m: 2_147_483_647,
+        shift: 0,
+        bits: 31,
+        broken: false,
+        seeding: Seeding::Minstd,
+    };
+
+    /// glibc `rand()` with the TYPE_0 state, as set up by `initstate` with 8 bytes
+    ///
+    /// The seed is an `unsigned int`, so only its low 32 bits are used.
+    pub const GLIBC_TYPE_0: LcgPreset = LcgPreset {
+        name: "glibc TYPE_0",
+        a: 1_103_515_245,
+        c: 12_345,
+        m: 1 << 31,
+        shift: 0,
+        bits: 31,
+        broken: false,
+        seeding: Seeding::Glibc,
+    };
+
+    /// Microsoft Visual C++ `rand()`, giving 15 bits from the top half of the state
+    pub const MSVC: LcgPreset = LcgPreset {
+        name: "MSVC",
+        a: 214_013,
+        c: 2_531_011,
+        m: 1 << 32,
+        shift: 16,
+        bits: 15,
+        broken: false,
+        seeding: Seeding::Plain,
+    };
+
+    /// `java.util.Random`, giving the 32 bits of `nextInt()`
+    ///
+    /// Cast the output to `u32` then `i32` to get the same signed value as Java.
+    pub const JAVA: LcgPreset = LcgPreset {
+        name: "java.util.Random",
+        a: 0x5_deec_e66d,
+        c: 11,
+        m: 1 << 48,
+        shift: 16,
+        bits: 32,
+        broken: false,
+        seeding: Seeding::Java,
+    };
+
+    /// POSIX `drand48`, giving all 48 bits of the state
+    ///
+    /// `drand48()` itself is the output divided by `2^48`.
+    pub const DRAND48: LcgPreset = LcgPreset {
+        name: "drand48",
+        a: 0x5_deec_e66d,
+        c: 11,
+        m: 1 << 48,
+        shift: 0,
+        bits: 48,
+        broken: false,
+        seeding: Seeding::Drand48,
+    };
+
+    /// POSIX `lrand48`, giving the top 31 bits of the `drand48` state
+    pub const LRAND48: LcgPreset = LcgPreset {
+        name: "lrand48",
+        a: 0x5_deec_e66d,
+        c: 11,
+        m: 1 << 48,
+        shift: 17,
+        bits: 31,
+        broken: false,
+        seeding: Seeding::Drand48,
+    };
+
+    /// `ranqd1` from Numerical Recipes in C, the "quick and dirty" generator
+    pub const NUMERICAL_RECIPES: LcgPreset = LcgPreset {
+        name: "Numerical Recipes",
+        a: 1_664_525,
+        c: 1_013_904_223,
+        m: 1 << 32,
+        shift: 0,
+        bits: 32,
+        broken: false,
+        seeding: Seeding::Plain,
+    };
+
+    /// Knuth's MMIX generator, giving the whole 64 bit state
+    pub const MMIX: LcgPreset = LcgPreset {
+        name: "MMIX",
+        a: 6_364_136_223_846_793_005,
+        c: 1_442_695_040_888_963_407,
+        m: 1 << 64,
+        shift: 0,
+        bits: 64,
+        broken: false,
+        seeding: Seeding::Plain,
+    };
+
+    /// Borland C/C++ `rand()`, giving 15 bits from the top half of the state
+    pub const BORLAND: LcgPreset = LcgPreset {
+        name: "Borland C",
+        a: 22_695_477,
+        c: 1,
+        m: 1 << 32,
+        shift: 16,
+        bits: 15,
+        broken: false,
+        seeding: Seeding::Plain,
+    };
+
+    /// IBM's RANDU
+    ///
+    /// **Broken**: every three outputs in a row lie on one of just 15 planes.
+    /// The seed should be odd.
+    pub const RANDU: LcgPreset = LcgPreset {
+        name: "RANDU",
+        a: 65_539,
+        c: 0,
+        m: 1 << 31,
+        shift: 0,
+        bits: 31,
+        broken: true,
+        seeding: Seeding::Plain,
+    };
+
+    /// Every preset
+    pub const ALL: &'static [LcgPreset] = &[
+        LcgPreset::MINSTD_RAND0,
+        LcgPreset::MINSTD_RAND,
+        LcgPreset::GLIBC_TYPE_0,
+        LcgPreset::MSVC,
+        LcgPreset::JAVA,
+        LcgPreset::DRAND48,
+        LcgPreset::LRAND48,
+        LcgPreset::NUMERICAL_RECIPES,
+        LcgPreset::MMIX,
+        LcgPreset::BORLAND,
+        LcgPreset::RANDU,
+    ];
+
+    /// Get the first state for a seed, the way the platform seeds it
+    pub const fn initial_state(&self, seed: u64) -> u64 {
+        match self.seeding {
+            Seeding::Plain => (seed as u128 % self.m) as u64,
+            Seeding::Minstd => match (seed as u128 % self.m) as u64 {
+                0 => 1,
+                i => i,
+            },
+            Seeding::Glibc => match seed as u32 {
+                0 => 1,
+                i => (i as u128 % self.m) as u64,
+            },
+            Seeding::Java => ((seed ^ self.a) as u128 % self.m) as u64,
+            Seeding::Drand48 => (seed as u32 as u64) << 16 | 0x330e,
+        }
+    }
+}
+
+/// Preset LCG Generator
+///
+/// Runs an [`LcgPreset`] exactly as the original platform did,
+/// so [`Lcg::next_output`] gives the same sequence bit for bit.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{Lcg, LcgPreset};
+///
+/// // Get the same numbers as `new java.util.Random(42).nextInt()`
+/// let mut r = Lcg::from_preset(&LcgPreset::JAVA, 42);
+/// assert_eq!(r.next_output() as u32 as i32, -1170105035);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Lcg {
+    state: u64,
+    a: u64,
+    c: u64,
+    m: u128,
+    shift: u32,
+    bits: u32,
+}
+
+impl Lcg {
+    /// Make a new generator from a preset, seeded the way the platform does it
+    pub const fn from_preset(preset: &LcgPreset, seed: u64) -> Lcg {
+        Lcg {
+            state: preset.initial_state(seed),
+            a: preset.a,
+            c: preset.c,
+            m: preset.m,
+            shift: preset.shift,
+            bits: preset.bits,
+        }
+    }
+
+    /// Step the generator, and get the output the platform would give
+    ///
+    /// This is `bits` wide, taken from the state starting at bit `shift`.
+    pub fn next_output(&mut self) -> u64 {
+        self.state = ((self.a as u128 * self.state as u128 + self.c as u128) % self.m) as u64;
+        self.state >> self.shift & u64::MAX >> (64 - self.bits)
+    }
+
+    /// Jump the generator forward by `n` steps
+    ///
+    /// Composes the step with itself by squaring, so it takes `O(log n)` time,
+    /// the same way as [`Random::advance`](crate::Random::advance).
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Lcg, LcgPreset};
+    ///
+    /// // Skip the first million outputs of MSVC rand()
+    /// let mut r = Lcg::from_preset(&LcgPreset::MSVC, 1);
+    /// r.advance(1_000_000);
+    /// let i = r.next_output();
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        let (a, c) = jump_map(self.a as u128, self.c as u128, self.m, n);
+        self.state = ((a * self.state as u128 + c) % self.m) as u64;
+    }
+
+    /// Split off a generator for the next `block` steps, and jump this one past them
+    ///
+    /// The new generator starts where this one is, so as long as it takes at most `block` steps
+    /// (calls of [`Lcg::next_output`]) it never overlaps with this one or any later split.
+    pub fn split(&mut self, block: u128) -> Lcg {
+        let child = self.clone();
+        self.advance(block);
+        child
+    }
+
+    /// Get the current state
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+}
+
+/// Compose the affine map `x -> a * x + c` mod `m` with itself `n` times, by squaring
+///
+/// `m` is at most `2^64` and `a` and `c` are below it, so every product fits in a `u128`.
+fn jump_map(a: u128, c: u128, m: u128, mut n: u128) -> (u128, u128) {
+    let mut acc_mult = 1 % m;
+    let mut acc_plus = 0;
+    let mut cur_mult = a;
+    let mut cur_plus = c;
+
+    while n > 0 {
+        if n & 1 == 1 {
+            acc_mult = acc_mult * cur_mult % m;
+            acc_plus = (acc_plus * cur_mult + cur_plus) % m;
+        }
+        cur_plus = (cur_mult + 1) * cur_plus % m;
+        cur_mult = cur_mult * cur_mult % m;
+        n >>= 1;
+    }
+    (acc_mult, acc_plus)
+}
+
+impl Default for Lcg {
+    /// Same as `Lcg::from_preset(&LcgPreset::MINSTD_RAND0, 1)`, like a default C++ `std::minstd_rand0`
+    fn default() -> Lcg {
+        Lcg::from_preset(&LcgPreset::MINSTD_RAND0, 1)
+    }
+}
+
+impl RandomSource for Lcg {
+    /// Made from as many outputs as needed, high bits first
+    ///
+    /// The outputs are used as they are, so any flaw in the platform's output carries over,
+    /// like MINSTD never giving 0. For evenly spread bits use [`Random`](crate::Random).
+    fn next_u32(&mut self) -> u32 {
+        join_outputs(self.bits, 32, || self.next_output()) as u32
+    }
+
+    /// Made from as many outputs as needed, high bits first
+    fn next_u64(&mut self) -> u64 {
+        join_outputs(self.bits, 64, || self.next_output())
+    }
+}
+
+impl SeedableRandom for Lcg {
+    type Seed = [u8; 8];
+
+    /// The seed is a little endian `u64`, run as a default C++ `std::minstd_rand0` seed
+    ///
+    /// Same as `Lcg::from_preset(&LcgPreset::MINSTD_RAND0, seed)`, like [`Lcg::default`].
+    fn from_seed(seed: [u8; 8]) -> Lcg {
+        Lcg::from_preset(&LcgPreset::MINSTD_RAND0, u64::from_le_bytes(seed))
+    }
+}
+
+impl SplitRandom for Lcg {
+    /// Same as [`Lcg::split`], with blocks of `m / 2^16` steps
+    fn fork(&mut self) -> Lcg {
+        let block = (self.m >> 16).max(1);
+        self.split(block)
+    }
+}
+
+/// Put together `width` bit outputs, high bits first, until there are at least `bits` of them
+fn join_outputs(width: u32, bits: u32, mut next: impl FnMut() -> u64) -> u64 {
+    let mut x = 0_u128;
+    let mut have = 0;
+    while have < bits {
+        x = x << width | next() as u128;
+        have += width;
+    }
+    (x >> (have - bits)) as u64
+}
+
+/// Define a power of two modulus LCG with a given state type
+macro_rules! impl_pow2_lcg {
+    ($name:ident, $t:ty) => {
+        #[doc = concat!("Power of two LCG Generator, with a `", stringify!($t), "` state")]
+        ///
+        /// The modulus is `2^k`, so each step is a wrapping multiply and add and a mask,
+        /// with no division. The low bits of such a generator are weak
+        /// (bit `i` has a period of at most `2^(i + 1)`), so the output is taken from the high bits:
+        /// by default the top half of the state, up to 64 bits.
+        /// Use `with_output` to pick the output bits.
+        /// ## Example
+        /// ```rust
+        /// // Import Lib
+        #[doc = concat!("use micro_rand::{", stringify!($name), ", LcgPreset, RandomExt, RandomSource};")]
+        ///
+        /// // A 48 bit generator, giving the top 32 bits
+        #[doc = concat!("let mut r = ", stringify!($name), "::new(1234, 0x5deece66d, 11, 48).with_output(16, 32);")]
+        /// let i = r.next_int_u32(0, 100);
+        ///
+        /// // Or run a power of two preset
+        #[doc = concat!("let mut r = ", stringify!($name), "::from_preset(&LcgPreset::MSVC, 1).unwrap();")]
+        /// assert_eq!(r.next_output(), 41);
+        /// ```
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct $name {
+            state: $t,
+            a: $t,
+            c: $t,
+            mask: $t,
+            shift: u32,
+            bits: u32,
+        }
+
+        impl $name {
+            #[doc = concat!("Make a new ", stringify!($name), " generator with modulus `2^modulus_bits`")]
+            ///
+            /// ## Panics
+            /// If the parameters are invalid.
+            /// Use the `try_new` constructor to get an error instead.
+            pub const fn new(seed: $t, a: $t, c: $t, modulus_bits: u32) -> $name {
+                match $name::try_new(seed, a, c, modulus_bits) {
+                    Ok(i) => i,
+                    Err(_) => panic!("invalid LCG parameters"),
+                }
+            }
+
+            #[doc = concat!("Make a new ", stringify!($name), " generator with modulus `2^modulus_bits`, checking the parameters first")]
+            ///
+            #[doc = concat!("Returns [`ParamError::ModulusNotPowerOfTwo`] if `modulus_bits` is 0 or more than ", stringify!($t), "::BITS,")]
+            /// and otherwise does the same checks as [`Random::try_custom_new`](crate::Random::try_custom_new).
+            pub const fn try_new(seed: $t, a: $t, c: $t, modulus_bits: u32) -> Result<$name, ParamError> {
+                if modulus_bits == 0 || modulus_bits > <$t>::BITS {
+                    return Err(ParamError::ModulusNotPowerOfTwo);
+                }
+
+                let mask = <$t>::MAX >> (<$t>::BITS - modulus_bits);
+                if a == 0 || a > mask {
+                    return Err(ParamError::MultiplierOutOfRange);
+                }
+                if c > mask {
+                    return Err(ParamError::IncrementOutOfRange);
+                }
+                if seed > mask {
+                    return Err(ParamError::SeedOutOfRange);
+                }
+
+                let bits = modulus_bits.div_ceil(2);
+                let bits = if bits > 64 { 64 } else { bits };
+                let r = $name {
+                    state: seed,
+                    a,
+                    c,
+                    mask,
+                    shift: modulus_bits - bits,
+                    bits,
+                };
+                if r.step() == seed {
+                    return Err(ParamError::FixedPointSeed);
+                }
+
+                Ok(r)
+            }
+
+            /// Make a new generator from a preset, seeded the way the platform does it
+            ///
+            /// Gives the same outputs as [`Lcg::from_preset`], with the faster step.
+            /// Returns [`ParamError::ModulusNotPowerOfTwo`] if the preset's modulus
+            /// is not a power of two, or is too big for the state.
+            pub const fn from_preset(preset: &LcgPreset, seed: u64) -> Result<$name, ParamError> {
+                let modulus_bits = preset.m.trailing_zeros();
+                if !preset.m.is_power_of_two() || modulus_bits > <$t>::BITS {
+                    return Err(ParamError::ModulusNotPowerOfTwo);
+                }
+
+                $name {
+                    state: preset.initial_state(seed) as $t,
+                    a: preset.a as $t,
+                    c: preset.c as $t,
+                    mask: <$t>::MAX >> (<$t>::BITS - modulus_bits),
+                    shift: 0,
+                    bits: 0,
+                }
+                .try_with_output(preset.shift, preset.bits)
+            }
+
+            /// Set which bits of the state are output: `bits` wide, starting at bit `shift`
+            ///
+            /// ## Panics
+            /// If the output bits don't fit in the state or are more than 64.
+            /// Use the `try_with_output` method to get an error instead.
+            pub const fn with_output(self, shift: u32, bits: u32) -> $name {
+                match self.try_with_output(shift, bits) {
+                    Ok(i) => i,
+                    Err(_) => panic!("invalid LCG output bits"),
+                }
+            }
+
+            /// Set which bits of the state are output: `bits` wide, starting at bit `shift`
+            ///
+            /// Returns [`ParamError::OutputOutOfRange`] if `bits` is 0 or more than 64,
+            /// or the output would go past the top of the state.
+            pub const fn try_with_output(self, shift: u32, bits: u32) -> Result<$name, ParamError> {
+                let modulus_bits = self.mask.count_ones();
+                if bits == 0 || bits > 64 || shift + bits > modulus_bits {
+                    return Err(ParamError::OutputOutOfRange);
+                }
+
+                Ok($name { shift, bits, ..self })
+            }
+
+            /// Check if the parameters give a period of the full modulus
+            ///
+            /// By the Hull–Dobell theorem, this is when `c` is odd and `a - 1` is divisible by 4
+            /// (or just by 2 for a modulus of 2). Multiplicative generators (`c == 0`)
+            /// never have a full period with a power of two modulus.
+            pub fn has_full_period(&self) -> bool {
+                let step = if self.mask == 1 { 2 } else { 4 };
+                self.c % 2 == 1 && (self.a.wrapping_sub(1) & self.mask) % step == 0
+            }
+
+            /// Step the generator, and get the output bits of the new state
+            pub fn next_output(&mut self) -> u64 {
+                self.state = self.step();
+                (self.state >> self.shift) as u64 & u64::MAX >> (64 - self.bits)
+            }
+
+            /// Jump the generator forward by `n` steps
+            ///
+            /// Takes `O(log n)` time. The period divides the modulus,
+            /// so jumping by the modulus leaves the generator where it was.
+            /// ## Example
+            /// ```rust
+            /// // Import Lib
+            #[doc = concat!("use micro_rand::{", stringify!($name), ", LcgPreset};")]
+            ///
+            /// // Skip a million numbers
+            #[doc = concat!("let mut r = ", stringify!($name), "::from_preset(&LcgPreset::MMIX, 1).unwrap();")]
+            /// r.advance(1_000_000);
+            /// let i = r.next_output();
+            /// ```
+            pub fn advance(&mut self, n: u128) {
+                let (a, c) = self.jump_map(self.a, self.c, n);
+                self.state = a.wrapping_mul(self.state).wrapping_add(c) & self.mask;
+            }
+
+            /// Step the generator back by `n` steps
+            ///
+            /// Runs the inverse map `x -> a⁻¹ * (x - c)` the same way as `advance`,
+            /// so after `step_back(n)` the next `n` outputs repeat the last `n`.
+            /// Returns [`ParamError::MultiplierNotInvertible`] if `a` is even.
+            pub fn step_back(&mut self, n: u128) -> Result<(), ParamError> {
+                if self.a % 2 == 0 {
+                    return Err(ParamError::MultiplierNotInvertible);
+                }
+
+                // Every odd `a` is its own inverse mod 8, and each Newton step doubles the correct bits
+                let mut a_inv = self.a;
+                for _ in 0..6 {
+                    a_inv = a_inv.wrapping_mul((2 as $t).wrapping_sub(self.a.wrapping_mul(a_inv)));
+                }
+                let c_inv = a_inv.wrapping_mul(self.c).wrapping_neg();
+
+                let (a, c) = self.jump_map(a_inv, c_inv, n);
+                self.state = a.wrapping_mul(self.state).wrapping_add(c) & self.mask;
+                Ok(())
+            }
+
+            /// Split off a generator for the next `block` steps, and jump this one past them
+            ///
+            /// The new generator starts where this one is, so as long as it takes at most `block` steps
+            /// (calls of `next_output`) it never overlaps with this one or any later split.
+            pub fn split(&mut self, block: u128) -> $name {
+                let child = self.clone();
+                self.advance(block);
+                child
+            }
+
+            /// Make the generator for worker `i` of `n`, taking every `n`th number from this one
+            ///
+            /// Worker `i` gets the outputs this generator would give at positions `i`, `i + n`, `i + 2n`, ...
+            /// (counting from 0), so together the workers get every output exactly once.
+            /// The new generator steps `n` steps at a time, so there is no waste.
+            /// Returns [`ParamError::MultiplierNotInvertible`] if `a` is even,
+            /// as every worker but the last starts behind this generator.
+            ///
+            /// ## Panics
+            /// If `n` is 0 or `i` is not less than `n`.
+            /// ## Example
+            /// ```rust
+            /// // Import Lib
+            #[doc = concat!("use micro_rand::{", stringify!($name), ", LcgPreset};")]
+            ///
+            /// // Worker 1 of 3 gets the 2nd, 5th, 8th, ... outputs
+            #[doc = concat!("let mut r = ", stringify!($name), "::from_preset(&LcgPreset::MMIX, 1).unwrap();")]
+            /// let mut w = r.leapfrog(1, 3).unwrap();
+            ///
+            /// r.next_output();
+            /// assert_eq!(w.next_output(), r.next_output());
+            /// ```
+            pub fn leapfrog(&self, i: u64, n: u64) -> Result<$name, ParamError> {
+                assert!(
+                    i < n,
+                    "worker index must be less than the number of workers"
+                );
+
+                // Start one leap before position i, so the first step lands on it
+                let mut start = self.clone();
+                match (i + 1).checked_sub(n) {
+                    Some(ahead) => start.advance(ahead as u128),
+                    None => start.step_back((n - i - 1) as u128)?,
+                }
+
+                let (a, c) = self.jump_map(self.a, self.c, n as u128);
+                Ok($name {
+                    a: a & self.mask,
+                    c: c & self.mask,
+                    ..start
+                })
+            }
+
+            /// Get the current state
+            pub fn state(&self) -> $t {
+                self.state
+            }
+
+            const fn step(&self) -> $t {
+                self.a.wrapping_mul(self.state).wrapping_add(self.c) & self.mask
+            }
+
+            /// Compose the affine map `x -> a * x + c` with itself `n` times, by squaring
+            ///
+            /// The maths is done mod `2^BITS`, which leaves the right value mod the modulus.
+            fn jump_map(&self, a: $t, c: $t, mut n: u128) -> ($t, $t) {
+                let mut acc_mult: $t = 1;
+                let mut acc_plus: $t = 0;
+                let mut cur_mult = a;
+                let mut cur_plus = c;
+
+                while n > 0 {
+                    if n & 1 == 1 {
+                        acc_mult = acc_mult.wrapping_mul(cur_mult);
+                        acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+                    }
+                    cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+                    cur_mult = cur_mult.wrapping_mul(cur_mult);
+                    n >>= 1;
+                }
+                (acc_mult, acc_plus)
+            }
+        }
+
+        impl RandomSource for $name {
+            /// Made from as many outputs as needed, high bits first
+            fn next_u32(&mut self) -> u32 {
+                join_outputs(self.bits, 32, || self.next_output()) as u32
+            }
+
+            /// Made from as many outputs as needed, high bits first
+            fn next_u64(&mut self) -> u64 {
+                join_outputs(self.bits, 64, || self.next_output())
+            }
+        }
+
+        impl SplitRandom for $name {
+            /// The child takes over the stream, and this generator jumps `2^(k - 16)` steps ahead
+            ///
+            /// For a modulus of `2^k`, so up to `2^16` children each get a separate block.
+            fn fork(&mut self) -> $name {
+                let block = 1 << self.mask.count_ones().saturating_sub(16);
+                self.split(block)
+            }
+        }
+    };
+}
+
+impl_pow2_lcg!(Lcg64, u64);
+impl_pow2_lcg!(Lcg128, u128);
+
+impl Default for Lcg64 {
+    /// Knuth's MMIX generator with seed 0, giving the top 32 bits of the state
+    ///
+    /// The same as `Lcg64::from_preset(&LcgPreset::MMIX, 0)` with `with_output(32, 32)`,
+    /// as the preset gives the whole state like Knuth's.
+    fn default() -> Lcg64 {
+        Lcg64::new(0, LcgPreset::MMIX.a, LcgPreset::MMIX.c, 64)
+    }
+}
+
+impl Default for Lcg128 {
+    /// The 128 bit LCG inside PCG64, with its default increment and seed 0
+    ///
+    /// The output is the high 64 bits of the state.
+    fn default() -> Lcg128 {
+        Lcg128::new(
+            0,
+            0x2360_ed05_1fc6_5da4_4385_df64_9fcc_f645,
+            0x5851_f42d_4c95_7f2d_1405_7b7e_f767_814f,
+            128,
+        )
+    }
+}
+
+impl SeedableRandom for Lcg64 {
+    type Seed = [u8; 8];
+
+    /// The seed is the first state, as a little endian `u64`, with the parameters of [`Lcg64::default`]
+    fn from_seed(seed: [u8; 8]) -> Lcg64 {
+        Lcg64 {
+            state: u64::from_le_bytes(seed),
+            ..Lcg64::default()
+        }
+    }
+}
+
+impl SeedableRandom for Lcg128 {
+    type Seed = [u8; 16];
+
+    /// The seed is the first state, as a little endian `u128`, with the parameters of [`Lcg128::default`]
+    fn from_seed(seed: [u8; 16]) -> Lcg128 {
+        Lcg128 {
+            state: u128::from_le_bytes(seed),
+            ..Lcg128::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lcg, Lcg128, Lcg64, LcgPreset};
+    use crate::error::ParamError;
+    use crate::{RandomSource, SeedableRandom, SplitMix64, SplitRandom};
+
+    fn outputs(preset: &LcgPreset, seed: u64, n: usize) -> [u64; 10] {
+        let mut r = Lcg::from_preset(preset, seed);
+        let mut out = [0; 10];
+        for i in out.iter_mut().take(n) {
+            *i = r.next_output();
+        }
+        out
+    }
+
+    #[test]
+    fn test_minstd() {
+        // The 10000th output with the default seed, from the C++ standard
+        let mut r = Lcg::from_preset(&LcgPreset::MINSTD_RAND0, 1);
+        for _ in 1..10_000 {
+            r.next_output();
+        }
+        assert_eq!(r.next_output(), 1_043_618_065);
+
+        let mut r = Lcg::from_preset(&LcgPreset::MINSTD_RAND, 1);
+        for _ in 1..10_000 {
+            r.next_output();
+        }
+        assert_eq!(r.next_output(), 399_268_537);
+
+        // Recorded from libstdc++ std::minstd_rand0(42)
+        assert_eq!(
+            outputs(&LcgPreset::MINSTD_RAND0, 42, 5)[..5],
+            [705894, 1126542223, 1579310009, 565444343, 807934826]
+        );
+
+        // A seed of 0 (mod m) becomes 1
+        assert_eq!(LcgPreset::MINSTD_RAND0.initial_state(0), 1);
+        assert_eq!(LcgPreset::MINSTD_RAND0.initial_state(2_147_483_647), 1);
+    }
+
+    #[test]
+    fn test_glibc() {
+        // Recorded from glibc random(), after initstate(42, state, 8)
+        assert_eq!(
+            outputs(&LcgPreset::GLIBC_TYPE_0, 42, 5)[..5],
+            [1250496027, 1116302264, 1000676753, 1668674806, 908095735]
+        );
+
+        // glibc seeds 0 as 1
+        assert_eq!(outputs(&LcgPreset::GLIBC_TYPE_0, 0, 1)[0], 1103527590);
+    }
+
+    #[test]
+    fn test_msvc() {
+        // MSVC rand() after srand(1)
+        assert_eq!(
+            outputs(&LcgPreset::MSVC, 1, 10),
+            [41, 18467, 6334, 26500, 19169, 15724, 11478, 29358, 26962, 24464]
+        );
+    }
+
+    #[test]
+    fn test_java() {
+        // new java.util.Random(42).nextInt()
+        let mut r = Lcg::from_preset(&LcgPreset::JAVA, 42);
+        let expected = [-1170105035, 234785527, -1360544799, 205897768, 1325939940];
+        for i in expected.iter() {
+            assert_eq!(r.next_output() as u32 as i32, *i);
+        }
+    }
+
+    #[test]
+    fn test_drand48() {
+        // Recorded from glibc lrand48() and drand48(), after srand48(42)
+        assert_eq!(
+            outputs(&LcgPreset::LRAND48, 42, 5)[..5],
+            [1598855263, 735945821, 238553827, 906966006, 174184913]
+        );
+
+        let mut r = Lcg::from_preset(&LcgPreset::DRAND48, 42);
+        let expected = [0.7445250000610066, 0.342701478718908, 0.11108528244416149];
+        for i in expected.iter() {
+            assert_eq!(r.next_output() as f64 / (1_u64 << 48) as f64, *i);
+        }
+    }
+
+    #[test]
+    fn test_numerical_recipes() {
+        // From Numerical Recipes in C, section 7.1, starting from 0
+        assert_eq!(
+            outputs(&LcgPreset::NUMERICAL_RECIPES, 0, 4)[..4],
+            [1013904223, 1196435762, 3519870697, 2868466484]
+        );
+    }
+
+    #[test]
+    fn test_mmix() {
+        assert_eq!(
+            outputs(&LcgPreset::MMIX, 1, 3)[..3],
+            [0x6c576fac43fd007c, 0x826886b3864a1b1b, 0xa5fae1992097aa0e]
+        );
+    }
+
+    #[test]
+    fn test_borland() {
+        // Borland rand() after srand(1)
+        assert_eq!(
+            outputs(&LcgPreset::BORLAND, 1, 5)[..5],
+            [346, 130, 10982, 1090, 11656]
+        );
+    }
+
+    #[test]
+    fn test_randu() {
+        assert_eq!(
+            outputs(&LcgPreset::RANDU, 1, 4)[..4],
+            [65539, 393225, 1769499, 7077969]
+        );
+
+        // Every output is a fixed combination of the two before it
+        let mut r = Lcg::from_preset(&LcgPreset::RANDU, 1);
+        let mut x = [r.next_output() as i64, r.next_output() as i64];
+        for _ in 0..1_000 {
+            let next = r.next_output() as i64;
+            assert_eq!(next, (6 * x[1] - 9 * x[0]).rem_euclid(1 << 31));
+            x = [x[1], next];
+        }
+
+        let broken = LcgPreset::ALL.iter().filter(|i| i.broken);
+        assert!(broken.map(|i| i.name).eq(["RANDU"].iter().copied()));
+    }
+
+    #[test]
+    fn test_random_source() {
+        // Three 15 bit outputs make up a u32, high bits first
+        let mut r = Lcg::from_preset(&LcgPreset::MSVC, 1);
+        let expected = (41_u64 << 30 | 18467 << 15 | 6334) >> 13;
+        assert_eq!(r.next_u32(), expected as u32);
+
+        // One 64 bit output is a u64
+        let mut r = Lcg::from_preset(&LcgPreset::MMIX, 1);
+        assert_eq!(r.next_u64(), 0x6c576fac43fd007c);
+        assert_eq!(r.next_u32(), 0x826886b3);
+    }
+
+    #[test]
+    fn test_pow2_from_preset() {
+        for preset in LcgPreset::ALL.iter() {
+            let mut r = Lcg::from_preset(preset, 42);
+            match (
+                Lcg64::from_preset(preset, 42),
+                Lcg128::from_preset(preset, 42),
+            ) {
+                (Ok(mut a), Ok(mut b)) => {
+                    for _ in 0..100 {
+                        let i = r.next_output();
+                        assert_eq!(a.next_output(), i);
+                        assert_eq!(b.next_output(), i);
+                    }
+                    assert_eq!(a.next_u64(), r.next_u64());
+                }
+                (a, b) => {
+                    assert_eq!(preset.m, 2_147_483_647);
+                    assert_eq!(a.err(), Some(ParamError::ModulusNotPowerOfTwo));
+                    assert_eq!(b.err(), Some(ParamError::ModulusNotPowerOfTwo));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pow2_try_new() {
+        assert!(Lcg64::try_new(1, 5, 3, 4).is_ok());
+        assert!(Lcg64::try_new(1, u64::MAX, 1, 64).is_ok());
+        assert!(Lcg128::try_new(1, u128::MAX, 1, 128).is_ok());
+
+        let cases = [
+            ((1, 5, 3, 0), ParamError::ModulusNotPowerOfTwo),
+            ((1, 5, 3, 65), ParamError::ModulusNotPowerOfTwo),
+            ((1, 0, 3, 4), ParamError::MultiplierOutOfRange),
+            ((1, 16, 3, 4), ParamError::MultiplierOutOfRange),
+            ((1, 5, 16, 4), ParamError::IncrementOutOfRange),
+            ((16, 5, 3, 4), ParamError::SeedOutOfRange),
+            ((0, 5, 0, 4), ParamError::FixedPointSeed),
+        ];
+        for ((seed, a, c, k), err) in cases.iter() {
+            assert_eq!(Lcg64::try_new(*seed, *a, *c, *k).err(), Some(*err));
+        }
+    }
+
+    #[test]
+    fn test_pow2_output() {
+        // By default the top 64 bits
+        let mut r = Lcg128::new(1, 0x2360_ed05_1fc6_5da4_4385_df64_9fcc_f645, 1, 128);
+        let i = r.next_output();
+        assert_eq!(i, (r.state() >> 64) as u64);
+
+        // Or the top half of a smaller state
+        let mut r = Lcg64::new(1234, 0x5_deec_e66d, 11, 48);
+        let i = r.next_output();
+        assert_eq!(i, r.state() >> 24);
+
+        // Two steps for each `u64`, so the weak low bits are never output
+        let mut mmix = Lcg64::default();
+        let mut plain = mmix.clone().with_output(0, 64);
+        let i = mmix.next_u64();
+        assert_eq!(i, plain.next_u64() >> 32 << 32 | plain.next_u64() >> 32);
+        let low = [0; 64].map(|_| mmix.next_u64() & 1);
+        assert!(low.windows(2).any(|i| i[0] == i[1]));
+
+        let mut r = r.with_output(16, 32);
+        let i = r.next_output();
+        assert_eq!(i, r.state() >> 16);
+
+        let r = Lcg64::new(1234, 0x5_deec_e66d, 11, 48);
+        let cases = [(0, 0), (0, 49), (17, 32), (48, 1)];
+        for (shift, bits) in cases.iter() {
+            let r = Lcg64::new(1234, 0x5_deec_e66d, 11, 48).try_with_output(*shift, *bits);
+            assert_eq!(r.err(), Some(ParamError::OutputOutOfRange));
+        }
+        assert!(Lcg128::new(1, 5, 1, 128).try_with_output(0, 65).is_err());
+        assert!(r.try_with_output(47, 1).is_ok());
+    }
+
+    #[test]
+    fn test_pow2_full_period() {
+        assert!(Lcg64::from_preset(&LcgPreset::MMIX, 1)
+            .unwrap()
+            .has_full_period());
+        assert!(Lcg64::from_preset(&LcgPreset::MSVC, 1)
+            .unwrap()
+            .has_full_period());
+        assert!(!Lcg64::from_preset(&LcgPreset::RANDU, 1)
+            .unwrap()
+            .has_full_period());
+        assert!(!Lcg64::new(1, 3, 1, 8).has_full_period());
+        assert!(!Lcg64::new(1, 5, 2, 8).has_full_period());
+        assert!(Lcg64::new(0, 1, 1, 1).has_full_period());
+
+        // Every state is visited once
+        let mut r = Lcg64::new(0, 5, 3, 8);
+        assert!(r.has_full_period());
+        let mut seen = [false; 256];
+        for _ in 0..256 {
+            r.next_output();
+            assert!(!seen[r.state() as usize]);
+            seen[r.state() as usize] = true;
+        }
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(
+            Lcg::default(),
+            Lcg::from_preset(&LcgPreset::MINSTD_RAND0, 1)
+        );
+        assert_eq!(
+            Lcg64::default(),
+            Lcg64::from_preset(&LcgPreset::MMIX, 0)
+                .unwrap()
+                .with_output(32, 32)
+        );
+
+        // The state steps like PCG64's
+        let mut r = Lcg128::default();
+        r.next_output();
+        assert_eq!(r.state(), 0x5851_f42d_4c95_7f2d_1405_7b7e_f767_814f);
+    }
+
+    #[test]
+    fn test_const_new() {
+        const JAVA: Lcg = Lcg::from_preset(&LcgPreset::JAVA, 42);
+        const MSVC: Result<Lcg64, ParamError> = Lcg64::from_preset(&LcgPreset::MSVC, 1);
+        assert_eq!(JAVA.clone().next_output() as u32 as i32, -1170105035);
+        assert_eq!(MSVC.unwrap().next_output(), 41);
+
+        static MMIX: Lcg64 =
+            Lcg64::new(1, LcgPreset::MMIX.a, LcgPreset::MMIX.c, 64).with_output(32, 32);
+        assert_eq!(
+            MMIX.clone().next_output(),
+            MMIX.a.wrapping_add(MMIX.c) >> 32
+        );
+    }
+
+    #[test]
+    fn test_advance() {
+        for preset in LcgPreset::ALL {
+            let mut a = Lcg::from_preset(preset, 1234);
+            let mut b = a.clone();
+            for _ in 0..1_000 {
+                b.next_output();
+            }
+            a.advance(1_000);
+            assert_eq!(a, b);
+
+            // A whole period of a power of two modulus comes back around
+            if preset.m.is_power_of_two() && preset.c % 2 == 1 {
+                a.advance(preset.m);
+                assert_eq!(a, b);
+            }
+        }
+
+        let mut a = Lcg128::default();
+        let mut b = a.clone();
+        for _ in 0..1_000 {
+            b.next_output();
+        }
+        a.advance(1_000);
+        assert_eq!(a, b);
+        a.advance(u128::MAX);
+        a.advance(1);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_step_back() {
+        let mut r = Lcg64::from_preset(&LcgPreset::JAVA, 42).unwrap();
+        let outputs = [r.next_output(), r.next_output(), r.next_output()];
+        r.step_back(3).unwrap();
+        assert_eq!(outputs, [r.next_output(), r.next_output(), r.next_output()]);
+
+        let mut r = Lcg128::default();
+        let state = r.state();
+        r.advance(1 << 100);
+        r.step_back(1 << 100).unwrap();
+        assert_eq!(r.state(), state);
+
+        let mut r = Lcg64::new(1, 2, 1, 32);
+        assert_eq!(r.step_back(1), Err(ParamError::MultiplierNotInvertible));
+    }
+
+    #[test]
+    fn test_leapfrog_union() {
+        for n in 1..6 {
+            let r = Lcg64::from_preset(&LcgPreset::MMIX, 1234).unwrap();
+            let mut plain = r.clone();
+            let mut workers = [0, 1, 2, 3, 4].map(|i| r.leapfrog(i % n, n).unwrap());
+            for _ in 0..100 {
+                for w in workers.iter_mut().take(n as usize) {
+                    assert_eq!(w.next_output(), plain.next_output());
+                }
+            }
+        }
+
+        let r = Lcg64::new(1, 6, 1, 32);
+        assert_eq!(r.leapfrog(0, 2), Err(ParamError::MultiplierNotInvertible));
+        assert!(r.leapfrog(1, 2).is_ok());
+    }
+
+    #[test]
+    fn test_split() {
+        let mut parent = Lcg::from_preset(&LcgPreset::MINSTD_RAND, 1);
+        let mut r = parent.clone();
+        let mut child = parent.fork();
+        for _ in 0..(2_147_483_647 >> 16) {
+            assert_eq!(child.next_output(), r.next_output());
+        }
+        assert_eq!(parent, r);
+
+        let mut parent = Lcg128::default();
+        let mut r = parent.clone();
+        let child = parent.fork();
+        assert_eq!(child, r);
+        r.advance(1 << 112);
+        assert_eq!(parent, r);
+
+        let mut parent = Lcg64::from_preset(&LcgPreset::MSVC, 1).unwrap();
+        let mut r = parent.clone();
+        parent.split(5);
+        r.advance(5);
+        assert_eq!(parent, r);
+    }
+
+    #[test]
+    fn test_from_seed() {
+        assert_eq!(
+            Lcg::from_seed(42_u64.to_le_bytes()),
+            Lcg::from_preset(&LcgPreset::MINSTD_RAND0, 42)
+        );
+        assert_eq!(
+            Lcg64::seed_from_u64(7).state(),
+            SplitMix64::new(7).next_u64()
+        );
+
+        let mut a = Lcg64::from_seed(1234_u64.to_le_bytes());
+        let mut b = Lcg64::from_preset(&LcgPreset::MMIX, 1234)
+            .unwrap()
+            .with_output(32, 32);
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        let r = Lcg128::from_seed(1234_u128.to_le_bytes());
+        assert_eq!(r.state(), 1234);
+        assert_eq!(Lcg128 { state: 0, ..r }, Lcg128::default());
+    }
+}
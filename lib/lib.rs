@@ -18,6 +18,44 @@ micro_rand = "0.0.1"
 
 Uses the Linear congruential generator algorithm to generate pseudo random numbers. You must supply a seed value in the form of a `i64` integer.
 
+Other generators are also included, all implementing the `RandomSource` trait:
+- `Lcg`: Runs an `LcgPreset`, bit for bit the same as MINSTD, glibc, MSVC, Java, `drand48`, Numerical Recipes, MMIX, Borland or RANDU
+- `Lcg64` / `Lcg128`: LCGs with a power of two modulus, giving only the strong high bits
+- `Mt19937` / `Mt19937_64`: Mersenne Twisters, matching C++ `std::mt19937`, Python and NumPy
+- `Pcg32` / `Pcg64`: Permuted congruential generators
+- `Xoshiro256StarStar`, `Xoshiro256Plus`, `Xoshiro128StarStar`, `Xoroshiro128Plus` and `Xoroshiro64Star`: Small, fast xor / shift / rotate generators with jump functions
+- `SplitMix64`: A tiny generator, also used to expand `u64` seeds for the others through the `SeedableRandom` trait
+- `WyRand`, `Sfc64` and `Sfc32`: The cheapest of the lot, for hot loops where speed matters most
+- `Philox4x32` / `Threefry2x64`: Counter based generators from Random123, giving any block directly from a key and counter, with `CounterRng` to use them in order
+
+None of those are cryptographically secure.
+With the `crypto` feature, `ChaCha8Rng`, `ChaCha12Rng` and `ChaCha20Rng` are also included.
+These use the ChaCha stream cipher and implement the `CryptoRandom` marker trait.
+So do `HashDrbg`, `HmacDrbg` and `CtrDrbg`, the deterministic random bit generators from NIST SP 800-90A.
+
+For parallel work, most generators implement `SplitRandom` to hand out independent child generators,
+and `Leapfrog` (or `Random::leapfrog`) deals out one stream between workers.
+`SeedSequence` (compatible with NumPy) turns any amount of entropy into seeds, and spawns a tree of child seeds.
+
+Seeds can also come from a string with `SeedableRandom::from_seed_str`.
+With the `getrandom` feature, `SeedableRandom::from_entropy` seeds any generator from the OS instead,
+returning an `EntropyError` if there is no entropy to be had. The crate stays `no_std` either way.
+For long running programs, `Reseeding` wraps any generator and mixes in fresh entropy
+from an `EntropySource` after a set number of bytes or calls, and after a fork with the `std` feature on Unix.
+
+`Random::to_bytes` saves the exact state of a generator in a fixed, versioned layout to checkpoint and resume later,
+and `Random::from_bytes` loads it back, returning a `StateError` for anything that isn't a valid saved state.
+With the `serde` feature, `Random` can be serialized the same way.
+
+Every generator can be cloned to replay a branch, compared, hashed and printed with `Debug`
+(the crypto generators print no key material), and most have a `Default` with a fixed seed.
+Constructors that take plain numbers, keys or presets are `const fn`,
+so a generator can live in a `static` on embedded targets.
+
+The `std` feature adds a generator for each thread, seeded from the OS the first time it is used.
+`random::<T>()` and `range(0..10)` take values from it, `thread_rng()` gives a handle to it,
+and `seed_thread_rng` reseeds it so tests can be reproducible.
+
 ## 💥 Examples
 Super Simple Example
 ```rust
@@ -35,5 +73,62 @@ let num2 = rnd.next_int_i64(0, 100);
 ```
 !*/
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "crypto")]
+mod chacha;
+mod counter;
+#[cfg(feature = "crypto")]
+mod drbg;
+mod entropy;
+mod error;
+mod ext;
+mod lcg;
+mod math;
+mod mt;
+mod pcg;
 mod random;
+mod reseed;
+mod sample;
+mod seed;
+mod seed_seq;
+mod sfc;
+mod source;
+mod split;
+mod splitmix;
+mod state;
+#[cfg(feature = "std")]
+mod thread;
+mod wyrand;
+mod xoshiro;
+#[cfg(feature = "crypto")]
+pub use chacha::{ChaCha12Rng, ChaCha20Rng, ChaCha8Rng};
+pub use counter::{CounterRandom, CounterRng, Philox4x32, Threefry2x64};
+#[cfg(feature = "crypto")]
+pub use drbg::{CtrDrbg, HashDrbg, HmacDrbg};
+pub use entropy::EntropySource;
+#[cfg(feature = "getrandom")]
+pub use entropy::{fill_entropy, OsEntropy};
+#[cfg(feature = "crypto")]
+pub use error::DrbgError;
+pub use error::{EntropyError, ParamError, StateError};
+pub use ext::RandomExt;
+pub use lcg::{Lcg, Lcg128, Lcg64, LcgPreset};
+pub use mt::{Mt19937, Mt19937_64};
+pub use pcg::{Pcg32, Pcg64};
 pub use random::Random;
+pub use reseed::Reseeding;
+pub use sample::{Sample, SampleRange};
+pub use seed::SeedableRandom;
+pub use seed_seq::SeedSequence;
+pub use sfc::{Sfc32, Sfc64};
+pub use source::{CryptoRandom, RandomSource};
+pub use split::{Leapfrog, SplitRandom};
+pub use splitmix::SplitMix64;
+#[cfg(feature = "std")]
+pub use thread::{random, range, seed_thread_rng, thread_rng, ThreadRng};
+pub use wyrand::WyRand;
+pub use xoshiro::{
+    Xoroshiro128Plus, Xoroshiro64Star, Xoshiro128StarStar, Xoshiro256Plus, Xoshiro256StarStar,
+};
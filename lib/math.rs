@@ -0,0 +1,223 @@
+//! Number theory helpers used to check generator parameters
+
+/// Compute the greatest common divisor of two numbers
+pub(crate) fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+/// Compute `(a * b) mod m` without overflowing
+pub(crate) fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// Compute `(base ^ exp) mod m` by square and multiply
+pub(crate) fn pow_mod(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut base = base % m;
+    let mut out = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            out = mul_mod(out, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    out
+}
+
+/// Compute the inverse of `a` mod `m`, if `a` and `m` are coprime
+///
+/// Uses the extended Euclidean algorithm.
+pub(crate) fn inverse_mod(a: u64, m: u64) -> Option<u64> {
+    let (mut r0, mut r1) = (m as i128, (a % m) as i128);
+    let (mut t0, mut t1) = (0_i128, 1_i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+
+    if r0 != 1 {
+        return None;
+    }
+    Some(t0.rem_euclid(m as i128) as u64)
+}
+
+/// Check if a number is prime
+///
+/// Uses Miller-Rabin with a set of bases that is deterministic for every `u64`.
+pub(crate) fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    for &p in BASES.iter() {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+
+    let shift = (n - 1).trailing_zeros();
+    let d = (n - 1) >> shift;
+    'outer: for &b in BASES.iter() {
+        let mut x = pow_mod(b, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..shift {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'outer;
+            }
+        }
+        return false;
+    }
+
+    true
+}
+
+/// The distinct prime factors of a number
+///
+/// A `u64` never has more than 15 distinct prime factors, so they are kept inline.
+pub(crate) struct PrimeFactors {
+    primes: [u64; 15],
+    len: usize,
+}
+
+impl PrimeFactors {
+    /// Factor a number
+    ///
+    /// Small factors are removed by trial division, the rest are found with Pollard's rho.
+    pub(crate) fn of(mut n: u64) -> PrimeFactors {
+        let mut out = PrimeFactors {
+            primes: [0; 15],
+            len: 0,
+        };
+
+        let mut p = 2;
+        while p < 1_000 && p * p <= n {
+            if n % p == 0 {
+                out.push(p);
+                while n % p == 0 {
+                    n /= p;
+                }
+            }
+            p += 1;
+        }
+
+        if n > 1 {
+            out.split(n);
+        }
+        out
+    }
+
+    /// Iterate over the prime factors
+    pub(crate) fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.primes[..self.len].iter().copied()
+    }
+
+    fn push(&mut self, p: u64) {
+        if !self.iter().any(|i| i == p) {
+            self.primes[self.len] = p;
+            self.len += 1;
+        }
+    }
+
+    fn split(&mut self, n: u64) {
+        if n == 1 {
+            return;
+        }
+        if is_prime(n) {
+            self.push(n);
+            return;
+        }
+
+        let d = pollard_rho(n);
+        self.split(d);
+        self.split(n / d);
+    }
+}
+
+/// Find a non trivial factor of a composite number
+fn pollard_rho(n: u64) -> u64 {
+    if n % 2 == 0 {
+        return 2;
+    }
+
+    let mut c = 1;
+    loop {
+        let f = |x: u64| ((mul_mod(x, x, n) as u128 + c) % n as u128) as u64;
+        let mut x = 2;
+        let mut y = 2;
+        let mut d = 1;
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+        c += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(17, 5), 1);
+        assert_eq!(gcd(0, 9), 9);
+    }
+
+    #[test]
+    fn test_pow_mod() {
+        assert_eq!(pow_mod(2, 10, 1_000), 24);
+        assert_eq!(pow_mod(16_807, 2_147_483_646, 2_147_483_647), 1);
+        assert_eq!(pow_mod(5, 0, 1), 0);
+    }
+
+    #[test]
+    fn test_inverse_mod() {
+        assert_eq!(inverse_mod(3, 7), Some(5));
+        assert_eq!(inverse_mod(16_807, 2_147_483_647), Some(1_407_677_000));
+        assert_eq!(inverse_mod(u64::MAX, u64::MAX - 1), Some(1));
+        assert_eq!(inverse_mod(6, 9), None);
+        assert_eq!(inverse_mod(5, 1), Some(0));
+    }
+
+    #[test]
+    fn test_is_prime() {
+        let primes: [u64; 6] = [
+            2,
+            3,
+            97,
+            2_147_483_647,
+            1_000_000_007,
+            18_446_744_073_709_551_557,
+        ];
+        let composites: [u64; 6] = [0, 1, 91, 3_215_031_751, 2_147_483_646, 7_263_957_720];
+        assert!(primes.iter().all(|&i| is_prime(i)));
+        assert!(composites.iter().all(|&i| !is_prime(i)));
+    }
+
+    #[test]
+    fn test_prime_factors() {
+        let f = PrimeFactors::of(2_147_483_646);
+        assert!(f.iter().eq([2, 3, 7, 11, 31, 151, 331].iter().copied()));
+
+        let f = PrimeFactors::of(4_611_686_014_132_420_609);
+        assert!(f.iter().eq([2_147_483_647].iter().copied()));
+
+        let f = PrimeFactors::of(1);
+        assert_eq!(f.iter().count(), 0);
+    }
+}
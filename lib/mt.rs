@@ -0,0 +1,410 @@
+use crate::{RandomSource, SeedableRandom, SplitRandom};
+
+const N32: usize = 624;
+const M32: usize = 397;
+const N64: usize = 312;
+const M64: usize = 156;
+
+/// MT19937 Generator
+///
+/// The 32 bit Mersenne Twister, with a period of `2^19937 - 1`.
+/// Gives the exact same output as `std::mt19937` in C++, `random` in Python
+/// and `RandomState` in NumPy when seeded the same way.
+/// The 2.5KB of state is held inline, so it needs no allocation.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{Mt19937, RandomSource};
+///
+/// // Same as `std::mt19937 r(5489);`
+/// let mut r = Mt19937::new(5489);
+/// assert_eq!(r.next_u32(), 3499211612);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mt19937 {
+    state: [u32; N32],
+    index: usize,
+}
+
+impl Mt19937 {
+    /// Make a new MT19937 generator from a u32 seed
+    ///
+    /// Works like `init_genrand` from the reference implementation,
+    /// and the `std::mt19937` constructor.
+    pub const fn new(seed: u32) -> Mt19937 {
+        let mut state = [0; N32];
+        state[0] = seed;
+        let mut i = 1;
+        while i < N32 {
+            let prev = state[i - 1];
+            state[i] = 1_812_433_253_u32
+                .wrapping_mul(prev ^ prev >> 30)
+                .wrapping_add(i as u32);
+            i += 1;
+        }
+
+        Mt19937 { state, index: N32 }
+    }
+
+    /// Make a new MT19937 generator from an array of u32s
+    ///
+    /// Works like `init_by_array` from the reference implementation.
+    /// This is how Python's `random.seed` and NumPy's `RandomState` use seeds that are arrays.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Mt19937, RandomSource};
+    ///
+    /// // Same as Python's `random.seed(0x456_00000345_00000234_00000123)`
+    /// let mut r = Mt19937::from_array(&[0x123, 0x234, 0x345, 0x456]);
+    /// assert_eq!(r.next_u32(), 1067595299);
+    /// ```
+    pub fn from_array(key: &[u32]) -> Mt19937 {
+        let mut mt = Mt19937::new(19_650_218);
+        let s = &mut mt.state;
+        let mut i = 1;
+        let mut j = 0;
+
+        for _ in 0..N32.max(key.len()) {
+            let prev = s[i - 1];
+            s[i] = (s[i] ^ (prev ^ prev >> 30).wrapping_mul(1_664_525))
+                .wrapping_add(key.get(j).copied().unwrap_or(0))
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N32 {
+                s[0] = s[N32 - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+
+        for _ in 0..N32 - 1 {
+            let prev = s[i - 1];
+            s[i] = (s[i] ^ (prev ^ prev >> 30).wrapping_mul(1_566_083_941)).wrapping_sub(i as u32);
+            i += 1;
+            if i >= N32 {
+                s[0] = s[N32 - 1];
+                i = 1;
+            }
+        }
+
+        s[0] = 0x8000_0000;
+        mt
+    }
+
+    /// Generate the next block of state
+    fn twist(&mut self) {
+        for i in 0..N32 {
+            let y = (self.state[i] & 0x8000_0000) | (self.state[(i + 1) % N32] & 0x7fff_ffff);
+            let mag = if y & 1 == 1 { 0x9908_b0df } else { 0 };
+            self.state[i] = self.state[(i + M32) % N32] ^ y >> 1 ^ mag;
+        }
+        self.index = 0;
+    }
+}
+
+impl Default for Mt19937 {
+    /// Same as `Mt19937::new(5489)`, the default seed of the reference code and C++
+    fn default() -> Mt19937 {
+        Mt19937::new(5489)
+    }
+}
+
+impl RandomSource for Mt19937 {
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= N32 {
+            self.twist();
+        }
+
+        let mut y = self.state[self.index];
+        self.index += 1;
+
+        y ^= y >> 11;
+        y ^= y << 7 & 0x9d2c_5680;
+        y ^= y << 15 & 0xefc6_0000;
+        y ^ y >> 18
+    }
+
+    /// Made from two outputs, high bits first
+    fn next_u64(&mut self) -> u64 {
+        (self.next_u32() as u64) << 32 | self.next_u32() as u64
+    }
+
+    /// Works like `genrand_res53` from the reference implementation,
+    /// matching Python's `random.random()` and NumPy's `RandomState.random_sample()`
+    fn next_f64(&mut self) -> f64 {
+        let a = (self.next_u32() >> 5) as f64;
+        let b = (self.next_u32() >> 6) as f64;
+        (a * 67_108_864.0 + b) / 9_007_199_254_740_992.0
+    }
+}
+
+impl SeedableRandom for Mt19937 {
+    type Seed = [u8; 4];
+
+    /// The seed is a little endian `u32`, passed to [`Mt19937::new`]
+    fn from_seed(seed: [u8; 4]) -> Mt19937 {
+        Mt19937::new(u32::from_le_bytes(seed))
+    }
+}
+
+impl SplitRandom for Mt19937 {
+    /// The child is seeded with [`Mt19937::from_array`] on the next 8 outputs
+    ///
+    /// A single `u32` seed would make children repeat after about `2^16` forks,
+    /// so 256 bits of the parent's output go into the key instead.
+    fn fork(&mut self) -> Mt19937 {
+        let mut key = [0; 8];
+        key.iter_mut().for_each(|i| *i = self.next_u32());
+        Mt19937::from_array(&key)
+    }
+}
+
+/// MT19937-64 Generator
+///
+/// The 64 bit Mersenne Twister, with a period of `2^19937 - 1`.
+/// Gives the exact same output as `std::mt19937_64` in C++ when seeded the same way.
+/// The 2.5KB of state is held inline, so it needs no allocation.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{Mt19937_64, RandomSource};
+///
+/// // Same as `std::mt19937_64 r(5489);`
+/// let mut r = Mt19937_64::new(5489);
+/// assert_eq!(r.next_u64(), 14514284786278117030);
+/// ```
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mt19937_64 {
+    state: [u64; N64],
+    index: usize,
+}
+
+impl Mt19937_64 {
+    /// Make a new MT19937-64 generator from a u64 seed
+    ///
+    /// Works like `init_genrand64` from the reference implementation,
+    /// and the `std::mt19937_64` constructor.
+    pub const fn new(seed: u64) -> Mt19937_64 {
+        let mut state = [0; N64];
+        state[0] = seed;
+        let mut i = 1;
+        while i < N64 {
+            let prev = state[i - 1];
+            state[i] = 6_364_136_223_846_793_005_u64
+                .wrapping_mul(prev ^ prev >> 62)
+                .wrapping_add(i as u64);
+            i += 1;
+        }
+
+        Mt19937_64 { state, index: N64 }
+    }
+
+    /// Make a new MT19937-64 generator from an array of u64s
+    ///
+    /// Works like `init_by_array64` from the reference implementation.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Mt19937_64, RandomSource};
+    ///
+    /// // Seed with the key from the reference test program
+    /// let mut r = Mt19937_64::from_array(&[0x12345, 0x23456, 0x34567, 0x45678]);
+    /// assert_eq!(r.next_u64(), 7266447313870364031);
+    /// ```
+    pub fn from_array(key: &[u64]) -> Mt19937_64 {
+        let mut mt = Mt19937_64::new(19_650_218);
+        let s = &mut mt.state;
+        let mut i = 1;
+        let mut j = 0;
+
+        for _ in 0..N64.max(key.len()) {
+            let prev = s[i - 1];
+            s[i] = (s[i] ^ (prev ^ prev >> 62).wrapping_mul(3_935_559_000_370_003_845))
+                .wrapping_add(key.get(j).copied().unwrap_or(0))
+                .wrapping_add(j as u64);
+            i += 1;
+            j += 1;
+            if i >= N64 {
+                s[0] = s[N64 - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+
+        for _ in 0..N64 - 1 {
+            let prev = s[i - 1];
+            s[i] = (s[i] ^ (prev ^ prev >> 62).wrapping_mul(2_862_933_555_777_941_757))
+                .wrapping_sub(i as u64);
+            i += 1;
+            if i >= N64 {
+                s[0] = s[N64 - 1];
+                i = 1;
+            }
+        }
+
+        s[0] = 1 << 63;
+        mt
+    }
+
+    /// Generate the next block of state
+    fn twist(&mut self) {
+        for i in 0..N64 {
+            let x =
+                (self.state[i] & 0xffff_ffff_8000_0000) | (self.state[(i + 1) % N64] & 0x7fff_ffff);
+            let mag = if x & 1 == 1 { 0xb502_6f5a_a966_19e9 } else { 0 };
+            self.state[i] = self.state[(i + M64) % N64] ^ x >> 1 ^ mag;
+        }
+        self.index = 0;
+    }
+}
+
+impl Default for Mt19937_64 {
+    /// Same as `Mt19937_64::new(5489)`, the default seed of the reference code and C++
+    fn default() -> Mt19937_64 {
+        Mt19937_64::new(5489)
+    }
+}
+
+impl RandomSource for Mt19937_64 {
+    /// The high 32 bits of [`RandomSource::next_u64`]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        if self.index >= N64 {
+            self.twist();
+        }
+
+        let mut x = self.state[self.index];
+        self.index += 1;
+
+        x ^= x >> 29 & 0x5555_5555_5555_5555;
+        x ^= x << 17 & 0x71d6_7fff_eda6_0000;
+        x ^= x << 37 & 0xfff7_eee0_0000_0000;
+        x ^ x >> 43
+    }
+}
+
+impl SeedableRandom for Mt19937_64 {
+    type Seed = [u8; 8];
+
+    /// The seed is a little endian `u64`, passed to [`Mt19937_64::new`]
+    fn from_seed(seed: [u8; 8]) -> Mt19937_64 {
+        Mt19937_64::new(u64::from_le_bytes(seed))
+    }
+}
+
+impl SplitRandom for Mt19937_64 {
+    /// The child is seeded with [`Mt19937_64::from_array`] on the next 4 outputs
+    fn fork(&mut self) -> Mt19937_64 {
+        let mut key = [0; 4];
+        key.iter_mut().for_each(|i| *i = self.next_u64());
+        Mt19937_64::from_array(&key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mt19937, Mt19937_64};
+    use crate::{RandomSource, SeedableRandom, SplitRandom};
+
+    #[test]
+    fn test_mt19937_10000th() {
+        // The C++ standard requires the 10000th output of a default constructed std::mt19937
+        let mut r = Mt19937::new(5489);
+        for _ in 0..9_999 {
+            r.next_u32();
+        }
+        assert_eq!(r.next_u32(), 4_123_659_995);
+    }
+
+    #[test]
+    fn test_mt19937_64_10000th() {
+        // The C++ standard requires the 10000th output of a default constructed std::mt19937_64
+        let mut r = Mt19937_64::new(5489);
+        for _ in 0..9_999 {
+            r.next_u64();
+        }
+        assert_eq!(r.next_u64(), 9_981_545_732_273_789_042);
+    }
+
+    #[test]
+    fn test_mt19937_from_array() {
+        // From mt19937ar.out, the reference test output
+        let mut r = Mt19937::from_array(&[0x123, 0x234, 0x345, 0x456]);
+        let expected = [
+            1067595299, 955945823, 477289528, 4107218783, 4228976476, 3344332714, 3355579695,
+            227628506, 810200273, 2591290167,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u32(), *i);
+        }
+    }
+
+    #[test]
+    fn test_mt19937_64_from_array() {
+        // From mt19937-64.out, the reference test output
+        let mut r = Mt19937_64::from_array(&[0x12345, 0x23456, 0x34567, 0x45678]);
+        let expected = [
+            7266447313870364031,
+            4946485549665804864,
+            16945909448695747420,
+            16394063075524226720,
+            4873882236456199058,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u64(), *i);
+        }
+    }
+
+    #[test]
+    fn test_mt19937_python() {
+        // Python's random.seed(x) is init_by_array on the 32 bit words of x,
+        // and random.random() is genrand_res53
+        let mut r = Mt19937::from_array(&[0x123, 0x234, 0x345, 0x456]);
+        assert_eq!(r.next_f64(), 0.24856890158782508);
+        assert_eq!(r.next_f64(), 0.11112762955044497);
+
+        let mut r = Mt19937::from_array(&[1234]);
+        assert_eq!(r.next_u32(), 4150886329);
+        assert_eq!(r.next_u32(), 3342196574);
+        assert_eq!(r.next_u32(), 1892932127);
+    }
+
+    #[test]
+    fn test_seed() {
+        let mut a = Mt19937::from_seed(5489_u32.to_le_bytes());
+        let mut b = Mt19937::new(5489);
+        assert_eq!(a.next_u32(), b.next_u32());
+
+        let mut a = Mt19937_64::from_seed(5489_u64.to_le_bytes());
+        let mut b = Mt19937_64::new(5489);
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut parent = Mt19937::new(5489);
+        let mut child = parent.fork();
+        let mut key = [0; 8];
+        let mut r = Mt19937::new(5489);
+        key.iter_mut().for_each(|i| *i = r.next_u32());
+        assert_eq!(child, Mt19937::from_array(&key));
+        assert_eq!(parent, r);
+        assert_ne!(child.next_u32(), parent.fork().next_u32());
+
+        let mut parent = Mt19937_64::new(5489);
+        let mut a = parent.fork();
+        let mut b = parent.fork();
+        assert_ne!(a.next_u64(), b.next_u64());
+        assert_eq!(parent.clone().fork(), parent.fork());
+    }
+}
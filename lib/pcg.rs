@@ -0,0 +1,360 @@
+use core::convert::TryInto;
+
+use crate::{RandomSource, SeedableRandom, SplitRandom};
+
+const PCG32_MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+const PCG64_MULTIPLIER: u128 = 0x2360_ed05_1fc6_5da4_4385_df64_9fcc_f645;
+
+/// PCG32 Generator
+///
+/// A permuted congruential generator, PCG-XSH-RR 64/32.
+/// It steps a 64 bit LCG and outputs 32 bits of the old state,
+/// shuffled with an xorshift and a random rotation.
+/// The period is `2^64`, and there are `2^63` separate streams picked by the increment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    /// Make a new PCG32 generator
+    ///
+    /// Works just like `pcg32_srandom_r` from the reference implementation.
+    /// `stream` picks the increment (`2 * stream + 1`), so the top bit of it is ignored.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Pcg32, RandomSource};
+    ///
+    /// // Make a new generator with seed 42 on stream 54
+    /// let mut r = Pcg32::new(42, 54);
+    /// assert_eq!(r.next_u32(), 0xa15c02b7);
+    /// ```
+    pub const fn new(state: u64, stream: u64) -> Pcg32 {
+        let mut pcg = Pcg32 {
+            state: 0,
+            increment: stream << 1 | 1,
+        };
+        pcg.step();
+        pcg.state = pcg.state.wrapping_add(state);
+        pcg.step();
+        pcg
+    }
+
+    /// Jump the generator forward by `delta` steps
+    ///
+    /// Takes `O(log delta)` time. As the period is `2^64`,
+    /// jumping by `2^64 - n` (`delta.wrapping_neg()`) goes back `n` steps.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Pcg32, RandomSource};
+    ///
+    /// // Skip a million numbers
+    /// let mut r = Pcg32::new(42, 54);
+    /// r.advance(1_000_000);
+    /// let i = r.next_u32();
+    /// ```
+    pub fn advance(&mut self, delta: u64) {
+        let mut acc_mult = 1_u64;
+        let mut acc_plus = 0_u64;
+        let mut cur_mult = PCG32_MULTIPLIER;
+        let mut cur_plus = self.increment;
+        let mut delta = delta;
+
+        while delta > 0 {
+            if delta & 1 == 1 {
+                acc_mult = acc_mult.wrapping_mul(cur_mult);
+                acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+            }
+            cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+            cur_mult = cur_mult.wrapping_mul(cur_mult);
+            delta >>= 1;
+        }
+
+        self.state = acc_mult.wrapping_mul(self.state).wrapping_add(acc_plus);
+    }
+
+    const fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(PCG32_MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+}
+
+impl Default for Pcg32 {
+    /// Same as `Pcg32::new(0, 0)`
+    fn default() -> Pcg32 {
+        Pcg32::new(0, 0)
+    }
+}
+
+impl RandomSource for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.step();
+
+        let rot = (state >> 59) as u32;
+        let xsh = ((state >> 18 ^ state) >> 27) as u32;
+        xsh.rotate_right(rot)
+    }
+
+    /// Made from two outputs, high bits first
+    fn next_u64(&mut self) -> u64 {
+        (self.next_u32() as u64) << 32 | self.next_u32() as u64
+    }
+}
+
+/// PCG64 Generator
+///
+/// A permuted congruential generator, PCG-XSL-RR 128/64.
+/// It steps a 128 bit LCG and outputs 64 bits of the new state,
+/// folded with an xor and a random rotation.
+/// The period is `2^128`, and there are `2^127` separate streams picked by the increment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pcg64 {
+    state: u128,
+    increment: u128,
+}
+
+impl Pcg64 {
+    /// Make a new PCG64 generator
+    ///
+    /// Works just like `pcg64_srandom_r` from the reference implementation.
+    /// `stream` picks the increment (`2 * stream + 1`), so the top bit of it is ignored.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Pcg64, RandomSource};
+    ///
+    /// // Make a new generator with seed 42 on stream 54
+    /// let mut r = Pcg64::new(42, 54);
+    /// assert_eq!(r.next_u64(), 0x86b1da1d72062b68);
+    /// ```
+    pub const fn new(state: u128, stream: u128) -> Pcg64 {
+        let mut pcg = Pcg64 {
+            state: 0,
+            increment: stream << 1 | 1,
+        };
+        pcg.step();
+        pcg.state = pcg.state.wrapping_add(state);
+        pcg.step();
+        pcg
+    }
+
+    /// Jump the generator forward by `delta` steps
+    ///
+    /// Takes `O(log delta)` time. As the period is `2^128`,
+    /// jumping by `2^128 - n` (`delta.wrapping_neg()`) goes back `n` steps.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Pcg64, RandomSource};
+    ///
+    /// // Skip a million numbers
+    /// let mut r = Pcg64::new(42, 54);
+    /// r.advance(1_000_000);
+    /// let i = r.next_u64();
+    /// ```
+    pub fn advance(&mut self, delta: u128) {
+        let mut acc_mult = 1_u128;
+        let mut acc_plus = 0_u128;
+        let mut cur_mult = PCG64_MULTIPLIER;
+        let mut cur_plus = self.increment;
+        let mut delta = delta;
+
+        while delta > 0 {
+            if delta & 1 == 1 {
+                acc_mult = acc_mult.wrapping_mul(cur_mult);
+                acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+            }
+            cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+            cur_mult = cur_mult.wrapping_mul(cur_mult);
+            delta >>= 1;
+        }
+
+        self.state = acc_mult.wrapping_mul(self.state).wrapping_add(acc_plus);
+    }
+
+    const fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(PCG64_MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+}
+
+impl Default for Pcg64 {
+    /// Same as `Pcg64::new(0, 0)`
+    fn default() -> Pcg64 {
+        Pcg64::new(0, 0)
+    }
+}
+
+impl RandomSource for Pcg64 {
+    /// The high 32 bits of [`RandomSource::next_u64`]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.step();
+
+        let rot = (self.state >> 122) as u32;
+        let xsl = (self.state >> 64) as u64 ^ self.state as u64;
+        xsl.rotate_right(rot)
+    }
+}
+
+impl SeedableRandom for Pcg32 {
+    type Seed = [u8; 16];
+
+    /// The seed is the state then the stream, as little endian `u64`s
+    fn from_seed(seed: [u8; 16]) -> Pcg32 {
+        let state = u64::from_le_bytes(seed[..8].try_into().unwrap());
+        let stream = u64::from_le_bytes(seed[8..].try_into().unwrap());
+        Pcg32::new(state, stream)
+    }
+}
+
+impl SeedableRandom for Pcg64 {
+    type Seed = [u8; 32];
+
+    /// The seed is the state then the stream, as little endian `u128`s
+    fn from_seed(seed: [u8; 32]) -> Pcg64 {
+        let state = u128::from_le_bytes(seed[..16].try_into().unwrap());
+        let stream = u128::from_le_bytes(seed[16..].try_into().unwrap());
+        Pcg64::new(state, stream)
+    }
+}
+
+impl SplitRandom for Pcg32 {
+    /// The child takes over the stream, and this generator jumps `2^48` steps ahead
+    ///
+    /// So up to `2^16` children each get a separate block of `2^48` numbers.
+    fn fork(&mut self) -> Pcg32 {
+        let child = Pcg32 {
+            state: self.state,
+            increment: self.increment,
+        };
+        self.advance(1 << 48);
+        child
+    }
+}
+
+impl SplitRandom for Pcg64 {
+    /// The child takes over the stream, and this generator jumps `2^96` steps ahead
+    ///
+    /// So up to `2^32` children each get a separate block of `2^96` numbers.
+    fn fork(&mut self) -> Pcg64 {
+        let child = Pcg64 {
+            state: self.state,
+            increment: self.increment,
+        };
+        self.advance(1 << 96);
+        child
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pcg32, Pcg64};
+    use crate::{RandomSource, SeedableRandom};
+
+    #[test]
+    fn test_pcg32_known_answer() {
+        // From the pcg32 reference demo, seeded with pcg32_srandom(42, 54)
+        let mut r = Pcg32::new(42, 54);
+        let expected = [
+            0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u32(), *i);
+        }
+    }
+
+    #[test]
+    fn test_pcg64_known_answer() {
+        // From the pcg64 reference demo, seeded with pcg64_srandom(42, 54)
+        let mut r = Pcg64::new(42, 54);
+        let expected = [
+            0x86b1da1d72062b68,
+            0x1304aa46c9853d39,
+            0xa3670e9e0dd50358,
+            0xf9090e529a7dae00,
+            0xc85b9fd837996f2c,
+            0x606121f8e3919196,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u64(), *i);
+        }
+    }
+
+    #[test]
+    fn test_pcg32_advance() {
+        for delta in [0, 1, 2, 20, 1_000].iter() {
+            let mut a = Pcg32::new(42, 54);
+            let mut b = Pcg32::new(42, 54);
+            for _ in 0..*delta {
+                a.next_u32();
+            }
+            b.advance(*delta);
+            assert_eq!(a.state, b.state);
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+
+        let mut r = Pcg32::new(42, 54);
+        let first = r.next_u32();
+        r.advance(1_u64.wrapping_neg());
+        assert_eq!(r.next_u32(), first);
+    }
+
+    #[test]
+    fn test_pcg64_advance() {
+        for delta in [0, 1, 2, 20, 1_000].iter() {
+            let mut a = Pcg64::new(42, 54);
+            let mut b = Pcg64::new(42, 54);
+            for _ in 0..*delta {
+                a.next_u64();
+            }
+            b.advance(*delta);
+            assert_eq!(a.state, b.state);
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+
+        let mut r = Pcg64::new(42, 54);
+        let first = r.next_u64();
+        r.advance(1_u128.wrapping_neg());
+        assert_eq!(r.next_u64(), first);
+    }
+
+    #[test]
+    fn test_streams() {
+        let mut a = Pcg32::new(42, 1);
+        let mut b = Pcg32::new(42, 2);
+        assert_ne!(a.increment, b.increment);
+        assert!((0..100).any(|_| a.next_u32() != b.next_u32()));
+
+        let mut a = Pcg64::new(42, 1);
+        let mut b = Pcg64::new(42, 2);
+        assert_ne!(a.increment, b.increment);
+        assert!((0..100).any(|_| a.next_u64() != b.next_u64()));
+    }
+
+    #[test]
+    fn test_seed() {
+        let mut seed = [0; 16];
+        seed[0] = 42;
+        seed[8] = 54;
+        let mut r = Pcg32::from_seed(seed);
+        assert_eq!(r.next_u32(), 0xa15c02b7);
+
+        let mut seed = [0; 32];
+        seed[0] = 42;
+        seed[16] = 54;
+        let mut r = Pcg64::from_seed(seed);
+        assert_eq!(r.next_u64(), 0x86b1da1d72062b68);
+    }
+}
@@ -1,4 +1,12 @@
+use core::convert::TryInto;
+
+use crate::error::{ParamError, StateError};
+use crate::math::{self, PrimeFactors};
+use crate::state;
+use crate::{RandomExt, RandomSource, SeedableRandom, SplitRandom};
+
 /// Random Generator
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Random {
     seed: i64,
     a: i64,
@@ -17,6 +25,10 @@ impl Random {
     /// c: 0
     /// m: 2147483647
     ///```
+    /// As `c` is 0, a seed of 0 (or any multiple of m) would get the generator stuck at 0,
+    /// so the seed is first normalized into `[1, m - 1]`.
+    /// Seeds already in that range are used as is, every other seed is reduced mod `m - 1`,
+    /// with 0 mapping to `m - 1`.
     /// ## Example
     /// ```rust
     /// // Import Lib
@@ -25,7 +37,12 @@ impl Random {
     /// // Make a new random generator with seed 1234
     /// let mut r = Random::new(1234);
     /// ```
-    pub fn new(seed: i64) -> Random {
+    pub const fn new(seed: i64) -> Random {
+        let seed = match seed.rem_euclid(2_147_483_646) {
+            0 => 2_147_483_646,
+            i => i,
+        };
+
         Random {
             seed,
             a: 16_807,
@@ -44,7 +61,7 @@ impl Random {
     /// // Make a new, custom random generator
     /// let mut r = Random::custom_new(1234, 86284, 2, 7263957720);
     /// ```
-    pub fn custom_new(seed: i64, a: i64, c: i64, m: i64) -> Random {
+    pub const fn custom_new(seed: i64, a: i64, c: i64, m: i64) -> Random {
         Random {
             seed,
             a,
@@ -54,6 +71,323 @@ impl Random {
         }
     }
 
+    /// Make a new random generator with custom values for a, c and m, checking them first
+    ///
+    /// Returns an error if `m` is not positive, `a` is not in `[1, m)`, `c` is not in `[0, m)`,
+    /// the seed is not in `[0, m)` or the seed is a fixed point of the generator.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{ParamError, Random};
+    ///
+    /// // Make a new, custom random generator
+    /// let r = Random::try_custom_new(1234, 86284, 2, 7263957720);
+    /// assert!(r.is_ok());
+    ///
+    /// // A zero modulus is rejected
+    /// let r = Random::try_custom_new(1234, 86284, 2, 0);
+    /// assert_eq!(r.err(), Some(ParamError::ZeroModulus));
+    /// ```
+    pub const fn try_custom_new(seed: i64, a: i64, c: i64, m: i64) -> Result<Random, ParamError> {
+        if m == 0 {
+            return Err(ParamError::ZeroModulus);
+        }
+        if m < 0 {
+            return Err(ParamError::NegativeModulus);
+        }
+        if a < 1 || a >= m {
+            return Err(ParamError::MultiplierOutOfRange);
+        }
+        if c < 0 || c >= m {
+            return Err(ParamError::IncrementOutOfRange);
+        }
+        if seed < 0 || seed >= m {
+            return Err(ParamError::SeedOutOfRange);
+        }
+
+        let r = Random::custom_new(seed, a, c, m);
+        if r.step() == seed {
+            return Err(ParamError::FixedPointSeed);
+        }
+
+        Ok(r)
+    }
+
+    /// Make a new random generator with custom values for a, c and m, checking that they have a full period
+    ///
+    /// Does all the checks of [`Random::try_custom_new`], then returns [`ParamError::NotFullPeriod`]
+    /// if [`Random::has_full_period`] is false.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{ParamError, Random};
+    ///
+    /// // The default parameters have a full period
+    /// let r = Random::try_custom_new_full_period(1234, 16807, 0, 2147483647);
+    /// assert!(r.is_ok());
+    ///
+    /// // But 2 is not a primitive root mod 7
+    /// let r = Random::try_custom_new_full_period(1, 2, 0, 7);
+    /// assert_eq!(r.err(), Some(ParamError::NotFullPeriod));
+    /// ```
+    pub fn try_custom_new_full_period(
+        seed: i64,
+        a: i64,
+        c: i64,
+        m: i64,
+    ) -> Result<Random, ParamError> {
+        let r = Random::try_custom_new(seed, a, c, m)?;
+        if !r.has_full_period() {
+            return Err(ParamError::NotFullPeriod);
+        }
+
+        Ok(r)
+    }
+
+    /// Check if the generator parameters give the longest possible period
+    ///
+    /// For mixed generators (`c != 0`) this uses the Hull–Dobell theorem, the period is `m` when:
+    /// - `c` and `m` are coprime
+    /// - `a - 1` is divisible by every prime factor of `m`
+    /// - `a - 1` is divisible by 4 if `m` is
+    ///
+    /// For multiplicative generators (`c == 0`) the period is `m - 1` when `m` is prime
+    /// and `a` is a primitive root mod `m`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::Random;
+    ///
+    /// // The default generator has a full period
+    /// let r = Random::new(1234);
+    /// assert!(r.has_full_period());
+    /// ```
+    pub fn has_full_period(&self) -> bool {
+        if self.m <= 0 || self.a <= 0 || self.c < 0 {
+            return false;
+        }
+
+        let a = self.a as u64 % self.m as u64;
+        let c = self.c as u64 % self.m as u64;
+        let m = self.m as u64;
+
+        if c != 0 {
+            let factors = PrimeFactors::of(m);
+            return math::gcd(c, m) == 1
+                && factors.iter().all(|p| ((a + m - 1) % m) % p == 0)
+                && (m % 4 != 0 || ((a + m - 1) % m) % 4 == 0);
+        }
+
+        if !math::is_prime(m) || a == 0 {
+            return false;
+        }
+
+        PrimeFactors::of(m - 1)
+            .iter()
+            .all(|q| math::pow_mod(a, (m - 1) / q, m) != 1)
+    }
+
+    /// Jump the generator forward by `n` steps
+    ///
+    /// Takes `O(log n)` time, by squaring the affine map `x -> a * x + c` mod `m`.
+    /// Each step is one call of [`Random::next_f64`]. Calls like [`Random::next_u32`]
+    /// take a varying number of steps, so jump by whole blocks to split a stream.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::Random;
+    ///
+    /// // Give a second worker the block of numbers after the first million
+    /// let a = Random::new(1234);
+    /// let mut b = Random::new(1234);
+    /// b.advance(1_000_000);
+    /// ```
+    pub fn advance(&mut self, n: u128) {
+        let m = self.m.unsigned_abs();
+        let a = self.a.rem_euclid(self.m) as u64;
+        let c = self.c.rem_euclid(self.m) as u64;
+        self.jump(a, c, m, n);
+    }
+
+    /// Step the generator back by `n` steps
+    ///
+    /// Runs the inverse map `x -> a⁻¹ * (x - c)` mod `m`, the same way as [`Random::advance`],
+    /// so after `step_back(n)` the next `n` calls of [`Random::next_f64`] repeat the last `n`.
+    /// Returns [`ParamError::MultiplierNotInvertible`] if `a` and `m` are not coprime,
+    /// as then different states can step to the same one.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::Random;
+    ///
+    /// // Go back to redo the last draw
+    /// let mut r = Random::new(1234);
+    /// let f = r.next_f64();
+    /// r.step_back(1).unwrap();
+    /// assert_eq!(r.next_f64(), f);
+    /// ```
+    pub fn step_back(&mut self, n: u128) -> Result<(), ParamError> {
+        let m = self.m.unsigned_abs();
+        let a = self.a.rem_euclid(self.m) as u64;
+        let c = self.c.rem_euclid(self.m) as u64;
+        let a_inv = math::inverse_mod(a, m).ok_or(ParamError::MultiplierNotInvertible)?;
+        let c_inv = math::mul_mod(a_inv, m - c, m);
+        self.jump(a_inv, c_inv, m, n);
+        Ok(())
+    }
+
+    /// Split off a generator for the next `block` steps, and jump this one past them
+    ///
+    /// The new generator starts where this one is, so as long as it takes at most `block` steps
+    /// (calls of [`Random::next_f64`]) it never overlaps with this one or any later split.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::Random;
+    ///
+    /// // Give each of 3 workers its own block of a thousand numbers
+    /// let mut parent = Random::new(1234);
+    /// let workers = [parent.split(1_000), parent.split(1_000), parent.split(1_000)];
+    /// ```
+    pub fn split(&mut self, block: u128) -> Random {
+        let child = Random { ..*self };
+        self.advance(block);
+        child
+    }
+
+    /// Make the generator for worker `i` of `n`, taking every `n`th number from this one
+    ///
+    /// Worker `i` gets the numbers this generator would give at positions `i`, `i + n`, `i + 2n`, ...
+    /// (counting from 0), so together the workers get every number exactly once.
+    /// The new generator is an LCG itself, stepping `n` steps at a time, so there is no waste.
+    /// Returns [`ParamError::MultiplierNotInvertible`] if `a` and `m` are not coprime,
+    /// as every worker but the last starts behind this generator.
+    ///
+    /// ## Panics
+    /// If `n` is 0 or `i` is not less than `n`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::Random;
+    ///
+    /// // Worker 1 of 3 gets the 2nd, 5th, 8th, ... numbers
+    /// let mut r = Random::new(1234);
+    /// let mut w = r.leapfrog(1, 3).unwrap();
+    ///
+    /// r.next_f64();
+    /// assert_eq!(w.next_f64(), r.next_f64());
+    /// ```
+    pub fn leapfrog(&self, i: u64, n: u64) -> Result<Random, ParamError> {
+        assert!(
+            i < n,
+            "worker index must be less than the number of workers"
+        );
+
+        // Start one leap before position i, so the first step lands on it
+        let mut start = Random { ..*self };
+        match (i + 1).checked_sub(n) {
+            Some(ahead) => start.advance(ahead as u128),
+            None => start.step_back((n - i - 1) as u128)?,
+        }
+
+        let m = self.m.unsigned_abs();
+        let a = self.a.rem_euclid(self.m) as u64;
+        let c = self.c.rem_euclid(self.m) as u64;
+        let (a, c) = jump_map(a, c, m, n as u128);
+        Ok(Random {
+            a: a as i64,
+            c: c as i64,
+            ..start
+        })
+    }
+
+    /// Length of a state saved with [`Random::to_bytes`]
+    pub const STATE_LEN: usize = 56;
+
+    /// Save the full state of the generator
+    ///
+    /// The layout is fixed, and the same on every platform:
+    ///```text
+    /// 0..4    magic "MRNG"
+    /// 4       format version, 1
+    /// 5       algorithm tag, 1 for Random
+    /// 6..8    reserved, 0
+    /// 8..16   seed
+    /// 16..24  a
+    /// 24..32  c
+    /// 32..40  m
+    /// 40..48  m the generator was made with
+    /// 48..56  FNV-1a hash of bytes 0..48
+    ///```
+    /// Every number is a little endian `i64`, except the hash which is a little endian `u64`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::Random;
+    ///
+    /// // Save a checkpoint, and carry on from it later
+    /// let mut r = Random::new(1234);
+    /// r.next_f64();
+    /// let saved = r.to_bytes();
+    ///
+    /// let mut resumed = Random::from_bytes(&saved).unwrap();
+    /// assert_eq!(resumed.next_f64(), r.next_f64());
+    /// ```
+    pub fn to_bytes(&self) -> [u8; Random::STATE_LEN] {
+        let mut buf = [0; Random::STATE_LEN];
+        let fields = [self.seed, self.a, self.c, self.m, self.start_m];
+        for (i, j) in buf[state::HEADER_LEN..]
+            .chunks_exact_mut(8)
+            .zip(fields.iter())
+        {
+            i.copy_from_slice(&j.to_le_bytes());
+        }
+        state::seal(&mut buf, state::RANDOM);
+        buf
+    }
+
+    /// Load a state saved with [`Random::to_bytes`]
+    ///
+    /// Returns an error if the bytes are not a saved state, are from a newer format version
+    /// or a different algorithm, are the wrong length or fail the checksum.
+    /// A modulus of 0 is also rejected, as that generator can't be stepped.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Random, StateError};
+    ///
+    /// // A changed byte is caught by the checksum
+    /// let mut saved = Random::new(1234).to_bytes();
+    /// saved[10] ^= 1;
+    /// assert_eq!(Random::from_bytes(&saved).err(), Some(StateError::ChecksumMismatch));
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Random, StateError> {
+        let fields = state::open(bytes, state::RANDOM, Random::STATE_LEN)?;
+        let mut f = fields
+            .chunks_exact(8)
+            .map(|i| i64::from_le_bytes(i.try_into().unwrap()));
+        let mut next = || f.next().unwrap();
+        let r = Random {
+            seed: next(),
+            a: next(),
+            c: next(),
+            m: next(),
+            start_m: next(),
+        };
+
+        if r.m == 0 {
+            return Err(StateError::InvalidState);
+        }
+        Ok(r)
+    }
+
+    /// Apply the affine map `x -> a * x + c` mod `m`, `n` times
+    fn jump(&mut self, a: u64, c: u64, m: u64, n: u128) {
+        let (a, c) = jump_map(a, c, m, n);
+        let seed = self.seed.rem_euclid(self.m) as u64;
+        self.seed = ((math::mul_mod(a, seed, m) + c) % m) as i64;
+    }
+
     /// Geth the next float 64 from a generator
     /// ## Example
     /// ```rust
@@ -67,15 +401,14 @@ impl Random {
     /// let f = r.next_f64();
     /// ```
     pub fn next_f64(&mut self) -> f64 {
-        let seed = self.seed;
-        let a = self.a;
-        let c = self.c;
-        let m = self.m;
-        self.seed = (a * seed + c) % m;
+        self.seed = self.step();
         self.seed as f64 / self.start_m as f64
     }
 
     /// Geth the next float 32 from a generator
+    ///
+    /// This is [`Random::next_f64`] rounded to an `f32`. States just below `m` round up to `1.0`,
+    /// so those are drawn again to keep the result in `[0, 1)`.
     /// ## Example
     /// ```rust
     /// // Import Lib
@@ -88,10 +421,19 @@ impl Random {
     /// let f = r.next_f32();
     /// ```
     pub fn next_f32(&mut self) -> f32 {
-        self.next_f64() as f32
+        loop {
+            let f = self.next_f64() as f32;
+            if f < 1.0 {
+                return f;
+            }
+        }
     }
 
     /// Geth the next i64 from a generator within a range
+    ///
+    /// Same as [`RandomExt::next_int_i64`], kept so callers don't need to import the trait.
+    /// ## Panics
+    /// If `min` is greater than `max`
     /// ## Example
     /// ```rust
     /// // Import Lib
@@ -104,11 +446,14 @@ impl Random {
     /// let f = r.next_int_i64(0, 100);
     /// ```
     pub fn next_int_i64(&mut self, min: i64, max: i64) -> i64 {
-        let x = self.next_f64();
-        (x * (max - min + 1) as f64 + min as f64) as i64
+        RandomExt::next_int_i64(self, min, max)
     }
 
     /// Geth the next i32 from a generator within a range
+    ///
+    /// Same as [`RandomExt::next_int_i32`], kept so callers don't need to import the trait.
+    /// ## Panics
+    /// If `min` is greater than `max`
     /// ## Example
     /// ```rust
     /// // Import Lib
@@ -121,14 +466,225 @@ impl Random {
     /// let f = r.next_int_i32(0, 100);
     /// ```
     pub fn next_int_i32(&mut self, min: i32, max: i32) -> i32 {
-        let x = self.next_f32();
-        (x * (max - min + 1) as f32 + min as f32) as i32
+        RandomExt::next_int_i32(self, min, max)
+    }
+
+    /// Get the next u32 from a generator
+    ///
+    /// Every bit is uniform. A single step can't fill a u32 (the default generator only has
+    /// `2^31 - 2` states), so the output is built from several steps.
+    /// With the default parameters this takes **2 steps**.
+    /// For custom parameters it takes the smallest number of steps `k` such that `n^k >= 2^56`,
+    /// where `n` is `|m| - 1` if `c` is 0 and `|m|` otherwise.
+    ///
+    /// Less than once in `2^24` calls the combined steps land in a range that can't be
+    /// split evenly, and all `k` steps are drawn again, so the stream is still reproducible.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::Random;
+    ///
+    /// // Make a new random generator
+    /// let mut r = Random::new(1234);
+    ///
+    /// // Get the next u32
+    /// let i = r.next_u32();
+    /// ```
+    pub fn next_u32(&mut self) -> u32 {
+        self.next_bits(32) as u32
+    }
+
+    /// Get the next u64 from a generator
+    ///
+    /// Every bit is uniform.
+    /// With the default parameters this takes **3 steps**.
+    /// For custom parameters it takes the smallest number of steps `k` such that `n^k >= 2^88`,
+    /// where `n` is `|m| - 1` if `c` is 0 and `|m|` otherwise
+    /// (or the largest `k` with `n^k < 2^128` if that comes first).
+    /// Just like [`Random::next_u32`], all `k` steps are redrawn less than once in `2^24` calls.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::Random;
+    ///
+    /// // Make a new random generator
+    /// let mut r = Random::new(1234);
+    ///
+    /// // Get the next u64
+    /// let i = r.next_u64();
+    /// ```
+    pub fn next_u64(&mut self) -> u64 {
+        self.next_bits(64)
+    }
+
+    /// Get a uniform integer in `[0, 2^bits)`, for `bits` up to 64
+    ///
+    /// Each step gives a digit in `[0, n)`, where `n` is the number of states the generator
+    /// cycles through (`|m| - 1` for multiplicative generators, `|m|` for mixed ones).
+    /// Digits are combined until there are at least `bits + 24` bits worth of them,
+    /// and the result is rejected if it falls in the last partial block of `2^bits`,
+    /// so every output is equally likely and a retry is needed less than once in `2^24` calls.
+    fn next_bits(&mut self, bits: u32) -> u64 {
+        let offset = if self.c == 0 { 1 } else { 0 };
+        let n = self.m.unsigned_abs().saturating_sub(offset).max(2) as u128;
+        let target = 1 << (bits + 24);
+        let block = 1 << bits;
+
+        loop {
+            let mut range = 1_u128;
+            let mut x = 0_u128;
+            while range < target {
+                let next = match range.checked_mul(n) {
+                    Some(i) => i,
+                    None => break,
+                };
+
+                self.seed = self.step();
+                x = x * n + (self.seed as u64).saturating_sub(offset) as u128;
+                range = next;
+            }
+
+            if x < range - range % block {
+                return (x % block) as u64;
+            }
+        }
+    }
+
+    /// Compute the next state of the generator
+    ///
+    /// The product `a * seed` can need up to 126 bits, so the step is done in `i128`
+    /// to get the exact `(a * seed + c) mod m` for any parameters that fit in the struct.
+    const fn step(&self) -> i64 {
+        let seed = self.seed as i128;
+        let a = self.a as i128;
+        let c = self.c as i128;
+        let m = self.m as i128;
+        (a * seed + c).rem_euclid(m) as i64
+    }
+}
+
+/// Compose the affine map `x -> a * x + c` mod `m` with itself `n` times, by squaring
+fn jump_map(a: u64, c: u64, m: u64, mut n: u128) -> (u64, u64) {
+    let mut acc_mult = 1 % m;
+    let mut acc_plus = 0;
+    let mut cur_mult = a;
+    let mut cur_plus = c;
+
+    while n > 0 {
+        if n & 1 == 1 {
+            acc_mult = math::mul_mod(acc_mult, cur_mult, m);
+            acc_plus = (math::mul_mod(acc_plus, cur_mult, m) + cur_plus) % m;
+        }
+        cur_plus = math::mul_mod(cur_mult + 1, cur_plus, m);
+        cur_mult = math::mul_mod(cur_mult, cur_mult, m);
+        n >>= 1;
+    }
+    (acc_mult, acc_plus)
+}
+
+impl Default for Random {
+    /// Same as `Random::new(1)`
+    ///
+    /// The default parameters are those of C++ `std::minstd_rand0`, and 1 is its default seed.
+    fn default() -> Random {
+        Random::new(1)
+    }
+}
+
+impl RandomSource for Random {
+    fn next_u32(&mut self) -> u32 {
+        Random::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Random::next_u64(self)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        Random::next_f64(self)
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        Random::next_f32(self)
+    }
+}
+
+impl SeedableRandom for Random {
+    type Seed = [u8; 8];
+
+    /// The seed is a little endian `i64`, passed to [`Random::new`]
+    fn from_seed(seed: [u8; 8]) -> Random {
+        Random::new(i64::from_le_bytes(seed))
+    }
+}
+
+impl SplitRandom for Random {
+    /// Same as [`Random::split`], with blocks of `m / 2^16` steps
+    ///
+    /// With the default parameters that is 32767 numbers for each of up to 65536 children,
+    /// after which they wrap around the period and start to overlap.
+    /// Use [`Random::split`] directly to pick the block size.
+    fn fork(&mut self) -> Random {
+        let block = (self.m.unsigned_abs() >> 16).max(1);
+        self.split(block as u128)
+    }
+}
+
+/// Serialized as the bytes of [`Random::to_bytes`]
+#[cfg(feature = "serde")]
+impl serde::Serialize for Random {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+/// Deserialized from the bytes of [`Random::to_bytes`], checked with [`Random::from_bytes`]
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Random {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Random, D::Error> {
+        use serde::de::{Error, SeqAccess, Visitor};
+
+        struct StateVisitor;
+
+        impl<'de> Visitor<'de> for StateVisitor {
+            type Value = Random;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{} bytes of saved Random state", Random::STATE_LEN)
+            }
+
+            fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Random, E> {
+                Random::from_bytes(v).map_err(E::custom)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Random, A::Error> {
+                let mut buf = [0; Random::STATE_LEN];
+                let mut len = 0;
+                while let Some(i) = seq.next_element()? {
+                    if len == buf.len() {
+                        return Err(A::Error::custom(StateError::WrongLength));
+                    }
+                    buf[len] = i;
+                    len += 1;
+                }
+                Random::from_bytes(&buf[..len]).map_err(A::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_bytes(StateVisitor)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Random;
+    extern crate std;
+
+    use core::hash::{Hash, Hasher};
+    use std::collections::hash_map::DefaultHasher;
+    use std::format;
+
+    use super::{ParamError, Random, StateError};
+    use crate::{RandomExt, RandomSource, SeedableRandom, SplitMix64, SplitRandom};
 
     #[test]
     fn test_new() {
@@ -140,6 +696,42 @@ mod tests {
         assert_eq!(r.start_m, 2_147_483_647);
     }
 
+    #[test]
+    fn test_new_seed_normalization() {
+        let m = 2_147_483_647;
+        let cases = [
+            (i64::MIN, 2_147_483_638),
+            (-1, m - 2),
+            (0, m - 1),
+            (1, 1),
+            (m - 1, m - 1),
+            (m, 1),
+            (i64::MAX, 7),
+        ];
+
+        for (seed, normalized) in cases.iter() {
+            let mut r = Random::new(*seed);
+            assert_eq!(r.seed, *normalized);
+            assert!(r.has_full_period());
+
+            for _ in 0..1_000 {
+                let f = r.next_f64();
+                assert!(f > 0.0 && f < 1.0);
+                assert!((0..=100).contains(&r.next_int_i64(0, 100)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        let r = Random::from_seed(1234_i64.to_le_bytes());
+        assert_eq!(r.seed, 1234);
+
+        let r = Random::seed_from_u64(1234);
+        let expected = Random::new(SplitMix64::new(1234).next_u64() as i64);
+        assert_eq!(r.seed, expected.seed);
+    }
+
     #[test]
     fn test_custom_new() {
         let r = Random::custom_new(4321, 86_284, 2, 7_263_957_720);
@@ -149,6 +741,81 @@ mod tests {
         assert_eq!(r.m, 7263957720);
     }
 
+    #[test]
+    fn test_try_custom_new() {
+        assert!(Random::try_custom_new(1234, 86_284, 2, 7_263_957_720).is_ok());
+        assert!(Random::try_custom_new(0, 5, 3, 16).is_ok());
+
+        let cases = [
+            ((1, 2, 0, 0), ParamError::ZeroModulus),
+            ((1, 2, 0, -7), ParamError::NegativeModulus),
+            ((1, 0, 0, 7), ParamError::MultiplierOutOfRange),
+            ((1, -3, 0, 7), ParamError::MultiplierOutOfRange),
+            ((1, 7, 0, 7), ParamError::MultiplierOutOfRange),
+            ((1, 3, -1, 7), ParamError::IncrementOutOfRange),
+            ((1, 3, 7, 7), ParamError::IncrementOutOfRange),
+            ((-1, 3, 0, 7), ParamError::SeedOutOfRange),
+            ((7, 3, 0, 7), ParamError::SeedOutOfRange),
+            ((0, 3, 0, 7), ParamError::FixedPointSeed),
+            ((4, 1, 0, 7), ParamError::FixedPointSeed),
+            ((3, 3, 1, 7), ParamError::FixedPointSeed),
+        ];
+        for ((seed, a, c, m), err) in cases.iter() {
+            assert_eq!(Random::try_custom_new(*seed, *a, *c, *m).err(), Some(*err));
+        }
+    }
+
+    /// Count the steps until the generator gets back to its seed
+    fn period(seed: i64, a: i64, c: i64, m: i64) -> Option<i64> {
+        let mut r = Random::custom_new(seed, a, c, m);
+        for i in 1..=m {
+            r.next_f64();
+            if r.seed == seed {
+                return Some(i);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_has_full_period() {
+        assert!(Random::new(1234).has_full_period());
+        assert!(Random::custom_new(1, 48_271, 0, 2_147_483_647).has_full_period());
+        assert!(!Random::custom_new(1, 65_539, 0, 2_147_483_648).has_full_period());
+        assert!(Random::custom_new(1, 1_103_515_245, 12_345, 2_147_483_648).has_full_period());
+
+        // Check the theory against the real period for every small generator
+        for m in 2..=64 {
+            for a in 1..m {
+                for c in 0..m {
+                    let r = Random::custom_new(1 % m, a, c, m);
+                    let full = if c == 0 { m - 1 } else { m };
+                    assert_eq!(
+                        r.has_full_period(),
+                        period(1 % m, a, c, m) == Some(full),
+                        "a: {}, c: {}, m: {}",
+                        a,
+                        c,
+                        m
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_custom_new_full_period() {
+        assert!(Random::try_custom_new_full_period(1234, 16_807, 0, 2_147_483_647).is_ok());
+        assert_eq!(
+            Random::try_custom_new_full_period(1, 2, 0, 7).err(),
+            Some(ParamError::NotFullPeriod)
+        );
+        assert_eq!(
+            Random::try_custom_new_full_period(1, 2, 0, 0).err(),
+            Some(ParamError::ZeroModulus)
+        );
+    }
+
     #[test]
     fn test_next_f64() {
         let mut r = Random::new(1234);
@@ -158,6 +825,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::excessive_precision)]
     fn test_next_f32() {
         let mut r = Random::new(1234);
         assert_eq!(r.next_f32(), 0.009657739666131204f32);
@@ -165,19 +833,372 @@ mod tests {
         assert_eq!(r.next_f32(), 0.41696758867100236f32);
     }
 
+    #[test]
+    fn test_next_f32_below_one() {
+        // The next state is m - 1, which rounds to 1.0 as an f32
+        let mut r = Random::custom_new(2_147_483_646, 16807, 0, 2_147_483_647);
+        r.step_back(1).unwrap();
+        assert_eq!(r.clone().next_f64() as f32, 1.0);
+
+        let f = r.next_f32();
+        assert!(f < 1.0);
+        assert_ne!(r.seed, 2_147_483_646);
+    }
+
+    /// Reference `(a * x + c) mod m` using only additions, so it can never overflow
+    fn reference_step(a: i64, c: i64, m: i64, x: i64) -> i64 {
+        let m = m as u64;
+        let add = |x: u64, y: u64| if x >= m - y { x - (m - y) } else { x + y };
+
+        let mut a = a as u64 % m;
+        let mut x = x as u64 % m;
+        let mut out = c as u64 % m;
+        while x > 0 {
+            if x & 1 == 1 {
+                out = add(out, a);
+            }
+            a = add(a, a);
+            x >>= 1;
+        }
+
+        out as i64
+    }
+
+    fn check_against_reference(seed: i64, a: i64, c: i64, m: i64, steps: usize) {
+        let mut r = Random::custom_new(seed, a, c, m);
+        let mut x = seed;
+        for _ in 0..steps {
+            x = reference_step(a, c, m, x);
+            r.next_f64();
+            assert_eq!(r.seed, x);
+        }
+    }
+
+    #[test]
+    fn test_next_f64_no_overflow() {
+        let mut r = Random::custom_new(1234, 86_284, 2, 7_263_957_720);
+        for _ in 0..1_000 {
+            let f = r.next_f64();
+            assert!((0.0..1.0).contains(&f));
+        }
+    }
+
+    #[test]
+    fn test_step_large_modulus() {
+        check_against_reference(1234, 86_284, 2, 7_263_957_720, 2_000_000);
+        check_against_reference(42, 3_935_559_000_370_003_845, 1, i64::MAX, 2_000_000);
+        check_against_reference(7, i64::MAX - 1, i64::MAX - 2, i64::MAX, 1_000_000);
+    }
+
     #[test]
     fn test_next_int_i64() {
         let mut r = Random::new(1234);
-        assert_eq!(r.next_int_i64(0, 100), 0);
         assert_eq!(r.next_int_i64(0, 100), 32);
-        assert_eq!(r.next_int_i64(0, 100), 42);
+        assert_eq!(r.next_int_i64(0, 100), 13);
+        assert_eq!(r.next_int_i64(0, 100), 38);
     }
 
     #[test]
     fn test_next_int_i32() {
         let mut r = Random::new(1234);
-        assert_eq!(r.next_int_i32(0, 100), 0);
-        assert_eq!(r.next_int_i32(0, 100), 32);
-        assert_eq!(r.next_int_i32(0, 100), 42);
+        assert_eq!(r.next_int_i32(0, 100), 65);
+        assert_eq!(r.next_int_i32(0, 100), 57);
+        assert_eq!(r.next_int_i32(0, 100), 66);
+    }
+
+    /// Count the steps a call takes
+    fn steps<T>(r: &mut Random, f: fn(&mut Random) -> T) -> usize {
+        let mut copy = Random::custom_new(r.seed, r.a, r.c, r.m);
+        f(r);
+        let mut i = 0;
+        while copy.seed != r.seed {
+            copy.next_f64();
+            i += 1;
+        }
+        i
+    }
+
+    #[test]
+    fn test_next_raw_steps() {
+        let mut r = Random::new(1234);
+        for _ in 0..1_000 {
+            assert_eq!(steps(&mut r, Random::next_u32), 2);
+            assert_eq!(steps(&mut r, Random::next_u64), 3);
+            assert_eq!(steps(&mut r, Random::next_u128), 6);
+            assert_eq!(steps(&mut r, Random::next_i64), 3);
+        }
+
+        let mut r = Random::custom_new(1, 86_284, 2, 7_263_957_720);
+        assert_eq!(steps(&mut r, Random::next_u32), 2);
+        assert_eq!(steps(&mut r, Random::next_u64), 3);
+
+        let mut r = Random::custom_new(1, 3_935_559_000_370_003_845, 1, i64::MAX);
+        assert_eq!(steps(&mut r, Random::next_u32), 1);
+        assert_eq!(steps(&mut r, Random::next_u64), 2);
+    }
+
+    #[test]
+    fn test_next_raw_negative_modulus() {
+        // The state is reduced into [0, |m|), so a negative modulus acts like |m|
+        let mut a = Random::custom_new(1234, 16807, 0, -2_147_483_647);
+        let mut b = Random::custom_new(1234, 16807, 0, 2_147_483_647);
+        for _ in 0..1_000 {
+            assert_eq!(a.next_u64(), b.next_u64());
+            assert_eq!(steps(&mut a, Random::next_u64), 3);
+            b.next_u64();
+        }
+
+        let mut r = Random::custom_new(1, 3_935_559_000_370_003_845, 0, i64::MIN);
+        assert_eq!(steps(&mut r, Random::next_u32), 1);
+        assert_eq!(steps(&mut r, Random::next_u64), 2);
+        r.next_u64();
+    }
+
+    #[test]
+    fn test_next_raw_values() {
+        let mut r = Random::new(1234);
+        assert_eq!(r.next_u32(), 2_788_110_425);
+        assert_eq!(r.next_u64(), 1_412_985_734_670_454_904);
+        assert_eq!(r.next_i32(), 1_421_171_503);
+    }
+
+    #[test]
+    fn test_advance() {
+        let params = [
+            (1234, 16_807, 0, 2_147_483_647),
+            (4321, 86_284, 2, 7_263_957_720),
+            (5, 1_103_515_245, 12_345, 1 << 31),
+            (1, 6, 1, 9),
+        ];
+        for (seed, a, c, m) in params.iter() {
+            for k in [0, 1, 2, 7, 100, 1_000].iter() {
+                let mut r = Random::custom_new(*seed, *a, *c, *m);
+                let mut s = Random::custom_new(*seed, *a, *c, *m);
+                r.advance(*k);
+                for _ in 0..*k {
+                    s.next_f64();
+                }
+                assert_eq!(r.seed, s.seed);
+                assert_eq!(r.next_f64(), s.next_f64());
+            }
+        }
+
+        // A whole period comes back around
+        let mut r = Random::new(1234);
+        r.advance(2_147_483_646);
+        assert_eq!(r.seed, 1234);
+        r.advance(2_147_483_646 * 1_000_000_007);
+        assert_eq!(r.seed, 1234);
+    }
+
+    #[test]
+    fn test_step_back() {
+        let params = [
+            (1234, 16_807, 0, 2_147_483_647),
+            (4321, 86_284, 3, 7_263_957_721),
+            (5, 1_103_515_245, 12_345, 1 << 31),
+        ];
+        for (seed, a, c, m) in params.iter() {
+            let mut r = Random::custom_new(*seed, *a, *c, *m);
+            let mut seen = [0; 10];
+            for i in seen.iter_mut() {
+                *i = r.seed;
+                r.next_f64();
+            }
+            for i in seen.iter().rev() {
+                r.step_back(1).unwrap();
+                assert_eq!(r.seed, *i);
+            }
+
+            r.advance(u128::MAX);
+            r.step_back(u128::MAX).unwrap();
+            assert_eq!(r.seed, *seed);
+        }
+
+        // 6 has no inverse mod 9
+        let mut r = Random::custom_new(1, 6, 1, 9);
+        assert_eq!(r.step_back(1), Err(ParamError::MultiplierNotInvertible));
+        assert_eq!(r.seed, 1);
+    }
+
+    #[test]
+    fn test_leapfrog_union() {
+        // Taking turns between the workers gives back the original stream
+        let params = [
+            (1234, 16_807, 0, 2_147_483_647),
+            (4321, 86_284, 3, 7_263_957_721),
+            (5, 1_103_515_245, 12_345, 1 << 31),
+        ];
+        for (seed, a, c, m) in params.iter() {
+            for n in 1..6 {
+                let mut r = Random::custom_new(*seed, *a, *c, *m);
+                let mut workers = [0, 1, 2, 3, 4].map(|i| r.leapfrog(i % n, n).unwrap());
+                for _ in 0..100 {
+                    for w in workers.iter_mut().take(n as usize) {
+                        assert_eq!(w.next_f64(), r.next_f64());
+                    }
+                }
+            }
+        }
+
+        // The last worker never has to step back
+        let mut r = Random::custom_new(1, 6, 1, 9);
+        let mut w = r.leapfrog(1, 2).unwrap();
+        r.next_f64();
+        assert_eq!(w.next_f64(), r.next_f64());
+        assert_eq!(
+            r.leapfrog(0, 2).err(),
+            Some(ParamError::MultiplierNotInvertible)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_leapfrog_bad_index() {
+        let _ = Random::new(1234).leapfrog(2, 2);
+    }
+
+    #[test]
+    fn test_split() {
+        // Each split gets the next block of the stream
+        let mut r = Random::new(1234);
+        let mut parent = Random::new(1234);
+        let mut children = [parent.split(10), parent.split(10), parent.split(10)];
+        for child in children.iter_mut() {
+            for _ in 0..10 {
+                assert_eq!(child.next_f64(), r.next_f64());
+            }
+        }
+        assert_eq!(parent.next_f64(), r.next_f64());
+
+        // Forks use blocks of m / 2^16
+        let mut parent = Random::new(1234);
+        let mut child = parent.fork();
+        child.advance(32_767);
+        assert_eq!(child.seed, parent.seed);
+    }
+
+    #[test]
+    fn test_state_layout() {
+        let saved = Random::new(1234).to_bytes();
+        assert_eq!(&saved[..8], b"MRNG\x01\x01\x00\x00");
+        assert_eq!(&saved[8..16], &1234_i64.to_le_bytes());
+        assert_eq!(&saved[16..24], &16807_i64.to_le_bytes());
+        assert_eq!(&saved[24..32], &0_i64.to_le_bytes());
+        assert_eq!(&saved[32..40], &2147483647_i64.to_le_bytes());
+        assert_eq!(&saved[40..48], &2147483647_i64.to_le_bytes());
+        assert_eq!(&saved[48..], &0x9fe606ff69735859_u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let mut r = Random::custom_new(-5, 86284, 2, 7263957720);
+        r.next_u64();
+        let mut resumed = Random::from_bytes(&r.to_bytes()).unwrap();
+        assert_eq!(resumed.to_bytes(), r.to_bytes());
+        for _ in 0..10 {
+            assert_eq!(resumed.next_u64(), r.next_u64());
+        }
+
+        // A leapfrog stream has its own a and c
+        let mut r = Random::new(1234).leapfrog(1, 3).unwrap();
+        let mut resumed = Random::from_bytes(&r.to_bytes()).unwrap();
+        assert_eq!(resumed.next_f64(), r.next_f64());
+    }
+
+    #[test]
+    fn test_state_errors() {
+        let saved = Random::new(1234).to_bytes();
+
+        assert_eq!(Random::from_bytes(&[]).err(), Some(StateError::BadMagic));
+        assert_eq!(
+            Random::from_bytes(&saved[1..]).err(),
+            Some(StateError::BadMagic)
+        );
+
+        let mut bad = saved;
+        bad[4] = 2;
+        assert_eq!(
+            Random::from_bytes(&bad).err(),
+            Some(StateError::UnsupportedVersion(2))
+        );
+
+        let mut bad = saved;
+        bad[5] = 7;
+        assert_eq!(
+            Random::from_bytes(&bad).err(),
+            Some(StateError::WrongAlgorithm(7))
+        );
+
+        assert_eq!(
+            Random::from_bytes(&saved[..40]).err(),
+            Some(StateError::WrongLength)
+        );
+
+        for i in 6..Random::STATE_LEN {
+            let mut bad = saved;
+            bad[i] ^= 0x10;
+            assert_eq!(
+                Random::from_bytes(&bad).err(),
+                Some(StateError::ChecksumMismatch)
+            );
+        }
+
+        let zero = Random::custom_new(1, 2, 3, 0).to_bytes();
+        assert_eq!(
+            Random::from_bytes(&zero).err(),
+            Some(StateError::InvalidState)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let mut r = Random::new(1234);
+        r.next_f64();
+        let json = serde_json::to_string(&r).unwrap();
+        let mut resumed: Random = serde_json::from_str(&json).unwrap();
+        assert_eq!(resumed.next_f64(), r.next_f64());
+
+        assert!(serde_json::from_str::<Random>("[1, 2, 3]").is_err());
+        let mut long = serde_json::to_value(&r).unwrap();
+        long.as_array_mut().unwrap().push(0.into());
+        assert!(serde_json::from_value::<Random>(long).is_err());
+    }
+
+    #[test]
+    fn test_std_traits() {
+        // Clone to replay a branch
+        let mut r = Random::new(1234);
+        r.next_f64();
+        let mut branch = r.clone();
+        assert_eq!(branch, r);
+        assert_eq!(branch.next_u64(), r.next_u64());
+        r.next_f64();
+        assert_ne!(branch, r);
+
+        let hash = |r: &Random| {
+            let mut h = DefaultHasher::new();
+            r.hash(&mut h);
+            h.finish()
+        };
+        assert_eq!(hash(&Random::new(5)), hash(&Random::new(5)));
+
+        assert_eq!(
+            format!("{:?}", Random::new(1234)),
+            "Random { seed: 1234, a: 16807, c: 0, m: 2147483647, start_m: 2147483647 }"
+        );
+
+        assert_eq!(Random::default(), Random::new(1));
+    }
+
+    #[test]
+    fn test_const_new() {
+        static R: Random = Random::new(0);
+        const C: Random = Random::custom_new(1, 2, 3, 5);
+        const T: Result<Random, ParamError> = Random::try_custom_new(1, 2, 3, 5);
+        assert_eq!(T, Ok(C));
+        assert_eq!(R, Random::new(0));
+        assert_eq!(C.clone().next_f64(), 0.0);
     }
 }
@@ -0,0 +1,407 @@
+use crate::error::EntropyError;
+use crate::{CryptoRandom, EntropySource, RandomSource, SeedableRandom};
+
+/// Reseeding adapter
+///
+/// Wraps any seedable generator, and reseeds it with fresh entropy
+/// after a set number of bytes or calls.
+/// The entropy goes in through [`SeedableRandom::reseed_with`]: most generators
+/// xor it with their own output to make the new seed, and the DRBGs run their own reseed.
+///
+/// With the `std` feature on Unix targets, a forked child process is also noticed
+/// and reseeded before its next output, so parent and child don't give the same values.
+/// If that reseed fails the output methods panic, as the child would otherwise repeat the parent.
+/// When the count runs out and the reseed fails, the generator carries on as it is;
+/// use [`Reseeding::try_fill_bytes`] to get the error instead.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{EntropyError, EntropySource, RandomSource, Reseeding, Xoshiro256StarStar};
+///
+/// struct Trng;
+///
+/// impl EntropySource for Trng {
+///     fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+///         dest.iter_mut().for_each(|i| *i = 0x5a);
+///         Ok(())
+///     }
+/// }
+///
+/// // Reseed every 64 KiB of output
+/// let mut r = Reseeding::new(Xoshiro256StarStar::new([1, 2, 3, 4]), Trng, 1 << 16);
+/// let i = r.next_u64();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Reseeding<G, E> {
+    generator: G,
+    source: E,
+    limit: u64,
+    remaining: u64,
+    by_calls: bool,
+    forks: usize,
+}
+
+impl<G: RandomSource + SeedableRandom, E: EntropySource> Reseeding<G, E> {
+    /// Make a new reseeding generator, reseeding after every `bytes` bytes of output
+    ///
+    /// [`RandomSource::next_u32`] counts as 4 bytes, [`RandomSource::next_u64`] as 8.
+    pub fn new(generator: G, source: E, bytes: u64) -> Reseeding<G, E> {
+        Reseeding {
+            generator,
+            source,
+            limit: bytes,
+            remaining: bytes,
+            by_calls: false,
+            forks: fork::count(),
+        }
+    }
+
+    /// Make a new reseeding generator, reseeding after every `calls` calls
+    ///
+    /// Each method of [`RandomSource`] counts as one call,
+    /// however many bytes it gives.
+    pub fn with_call_limit(generator: G, source: E, calls: u64) -> Reseeding<G, E> {
+        Reseeding {
+            by_calls: true,
+            ..Reseeding::new(generator, source, calls)
+        }
+    }
+
+    /// Reseed now
+    ///
+    /// If the source fails, the generator is left as it was and the error is returned.
+    /// Otherwise the byte or call count starts over.
+    pub fn reseed(&mut self) -> Result<(), EntropyError> {
+        let mut entropy = G::Seed::default();
+        self.source.fill_entropy(entropy.as_mut())?;
+        self.generator.reseed_with(entropy);
+
+        self.remaining = self.limit;
+        self.forks = fork::count();
+        Ok(())
+    }
+
+    /// Fill a buffer with random bytes, reseeding first if it is due
+    ///
+    /// Unlike [`RandomSource::fill_bytes`], a failed reseed is never skipped:
+    /// the error is returned, nothing is written, and the next call tries again.
+    pub fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        if self.forks != fork::count() || self.cost(dest.len()) > self.remaining {
+            self.reseed()?;
+        }
+        self.take(dest.len());
+        self.generator.fill_bytes(dest);
+        Ok(())
+    }
+
+    /// Get the wrapped generator
+    pub fn generator(&self) -> &G {
+        &self.generator
+    }
+
+    /// Get what an output of `bytes` bytes counts as
+    fn cost(&self, bytes: usize) -> u64 {
+        if self.by_calls {
+            1
+        } else {
+            bytes as u64
+        }
+    }
+
+    /// Count an output of `bytes` bytes, reseeding first if it is due
+    ///
+    /// If the reseed after a count runs out fails, the generator carries on as it is,
+    /// and the next try is after another full count.
+    ///
+    /// ## Panics
+    /// If the process has forked and the reseed fails
+    fn take(&mut self, bytes: usize) {
+        let cost = self.cost(bytes);
+        if self.forks != fork::count() {
+            if let Err(e) = self.reseed() {
+                panic!("could not reseed after a fork: {}", e);
+            }
+        } else if cost > self.remaining && self.reseed().is_err() {
+            self.remaining = self.limit;
+        }
+        self.remaining = self.remaining.saturating_sub(cost);
+    }
+}
+
+impl<G: RandomSource + SeedableRandom, E: EntropySource> RandomSource for Reseeding<G, E> {
+    fn next_u32(&mut self) -> u32 {
+        self.take(4);
+        self.generator.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.take(8);
+        self.generator.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.take(dest.len());
+        self.generator.fill_bytes(dest)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        self.take(8);
+        self.generator.next_f64()
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        self.take(4);
+        self.generator.next_f32()
+    }
+}
+
+impl<G: CryptoRandom + SeedableRandom, E: EntropySource> CryptoRandom for Reseeding<G, E> {}
+
+/// Fork detection, by counting forks in the child with `pthread_atfork`
+#[cfg(all(feature = "std", unix))]
+mod fork {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Once;
+
+    static FORKS: AtomicUsize = AtomicUsize::new(0);
+    static WATCH: Once = Once::new();
+
+    extern "C" fn child() {
+        FORKS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the number of forks this process has been through, watching for them from the first call
+    pub fn count() -> usize {
+        WATCH.call_once(|| {
+            // Safety: `child` is a plain function that is safe to run in the child after a fork.
+            // If the handler can't be registered, forks just go unnoticed.
+            unsafe {
+                libc::pthread_atfork(None, None, Some(child));
+            }
+        });
+        FORKS.load(Ordering::Relaxed)
+    }
+}
+
+/// No fork detection without `std` or outside Unix
+#[cfg(not(all(feature = "std", unix)))]
+mod fork {
+    pub fn count() -> usize {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::Reseeding;
+    use crate::error::EntropyError;
+    #[cfg(feature = "crypto")]
+    use crate::HashDrbg;
+    use crate::{EntropySource, Lcg64, Random, RandomSource, SeedableRandom, SplitMix64};
+
+    /// Gives a fixed byte, counting the calls
+    struct Fixed<'a> {
+        byte: u8,
+        calls: &'a Cell<u32>,
+    }
+
+    impl EntropySource for Fixed<'_> {
+        fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+            self.calls.set(self.calls.get() + 1);
+            dest.iter_mut().for_each(|i| *i = self.byte);
+            Ok(())
+        }
+    }
+
+    /// Fails once the flag is set
+    struct Switch<'a> {
+        fail: &'a Cell<bool>,
+    }
+
+    impl EntropySource for Switch<'_> {
+        fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+            match self.fail.get() {
+                true => Err(EntropyError::Unavailable),
+                false => {
+                    dest.iter_mut().for_each(|i| *i = 1);
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Always fails
+    struct Broken;
+
+    impl EntropySource for Broken {
+        fn fill_entropy(&mut self, _dest: &mut [u8]) -> Result<(), EntropyError> {
+            Err(EntropyError::Unavailable)
+        }
+    }
+
+    #[test]
+    fn test_reseed_after_bytes() {
+        let calls = Cell::new(0);
+        let source = Fixed {
+            byte: 0,
+            calls: &calls,
+        };
+        let mut r = Reseeding::new(SplitMix64::new(1234), source, 16);
+        let mut plain = SplitMix64::new(1234);
+
+        assert_eq!(r.next_u64(), plain.next_u64());
+        assert_eq!(r.next_u32(), plain.next_u32());
+        assert_eq!(r.next_u32(), plain.next_u32());
+        assert_eq!(calls.get(), 0);
+
+        // With zero entropy, the new seed is just the next output
+        let mut plain = SplitMix64::new(plain.next_u64());
+        assert_eq!(r.next_u64(), plain.next_u64());
+        assert_eq!(calls.get(), 1);
+
+        let mut bytes = [0; 8];
+        r.fill_bytes(&mut bytes);
+        assert_eq!(calls.get(), 1);
+        r.next_u32();
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_reseed_after_calls() {
+        let calls = Cell::new(0);
+        let source = Fixed {
+            byte: 0xa5,
+            calls: &calls,
+        };
+        let mut r = Reseeding::with_call_limit(Random::new(1234), source, 3);
+        let mut plain = Random::new(1234);
+        for _ in 0..3 {
+            assert_eq!(r.next_u64(), plain.next_u64());
+        }
+        assert_eq!(calls.get(), 0);
+
+        let seed = plain.next_u64() ^ 0xa5a5_a5a5_a5a5_a5a5;
+        let mut plain = Random::from_seed(seed.to_le_bytes());
+        assert_eq!(r.next_f64(), plain.next_f64());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_broken_source() {
+        let mut r = Reseeding::new(SplitMix64::new(1234), Broken, 8);
+        let mut plain = SplitMix64::new(1234);
+        for _ in 0..10 {
+            assert_eq!(r.next_u64(), plain.next_u64());
+        }
+        assert_eq!(r.reseed(), Err(EntropyError::Unavailable));
+    }
+
+    #[test]
+    fn test_try_fill_bytes() {
+        let fail = Cell::new(true);
+        let mut r = Reseeding::new(SplitMix64::new(1234), Switch { fail: &fail }, 8);
+        let mut plain = SplitMix64::new(1234);
+        let mut bytes = [0; 8];
+        r.try_fill_bytes(&mut bytes).unwrap();
+        assert_eq!(u64::from_le_bytes(bytes), plain.next_u64());
+
+        let mut bytes = [0; 4];
+        assert_eq!(r.try_fill_bytes(&mut bytes), Err(EntropyError::Unavailable));
+        assert_eq!(bytes, [0; 4]);
+        assert_eq!(r.try_fill_bytes(&mut bytes), Err(EntropyError::Unavailable));
+        assert_eq!(r.generator(), &plain);
+
+        // The next call tries again
+        fail.set(false);
+        r.try_fill_bytes(&mut bytes).unwrap();
+        let mut expected = [0; 4];
+        SplitMix64::new(plain.next_u64() ^ 0x0101_0101_0101_0101).fill_bytes(&mut expected);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_lcg() {
+        let calls = Cell::new(0);
+        let source = Fixed {
+            byte: 0,
+            calls: &calls,
+        };
+        let mut r = Reseeding::with_call_limit(Lcg64::default(), source, 1);
+        let mut plain = Lcg64::default();
+        assert_eq!(r.next_u64(), plain.next_u64());
+
+        let seed = plain.next_u64().to_le_bytes();
+        assert_eq!(r.next_u64(), Lcg64::from_seed(seed).next_u64());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "crypto")]
+    fn test_drbg() {
+        // The DRBG reseeds itself with the entropy, instead of taking a new seed
+        let calls = Cell::new(0);
+        let source = Fixed {
+            byte: 5,
+            calls: &calls,
+        };
+        let mut r = Reseeding::new(HashDrbg::from_seed([1; 32]), source, 16);
+        let mut plain = HashDrbg::from_seed([1; 32]);
+        assert_eq!(r.next_u64(), plain.next_u64());
+        assert_eq!(r.next_u64(), plain.next_u64());
+        assert_eq!(r.generator().reseed_counter(), 3);
+
+        plain.reseed(&[5; 32], &[]).unwrap();
+        assert_eq!(r.next_u64(), plain.next_u64());
+        assert_eq!(r.generator(), &plain);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn test_fork_broken_source() {
+        let fail = Cell::new(false);
+        let mut r = Reseeding::new(SplitMix64::new(1234), Switch { fail: &fail }, u64::MAX);
+
+        let pid = unsafe { libc::fork() };
+        if pid == 0 {
+            // The child must not carry on with the parent's stream
+            fail.set(true);
+            let out = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| r.next_u64()));
+            unsafe { libc::_exit(out.is_ok() as i32) }
+        }
+
+        let mut status = 0;
+        assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+        assert!(libc::WIFEXITED(status));
+        assert_eq!(libc::WEXITSTATUS(status), 0);
+        r.next_u64();
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn test_fork() {
+        let calls = Cell::new(0);
+        let source = Fixed {
+            byte: 1,
+            calls: &calls,
+        };
+        let mut r = Reseeding::new(SplitMix64::new(1234), source, u64::MAX);
+
+        let pid = unsafe { libc::fork() };
+        if pid == 0 {
+            // In the child, the next output must reseed
+            r.next_u64();
+            unsafe { libc::_exit((calls.get() != 1) as i32) }
+        }
+
+        let mut status = 0;
+        assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+        assert!(libc::WIFEXITED(status));
+        assert_eq!(libc::WEXITSTATUS(status), 0);
+
+        r.next_u64();
+        assert_eq!(calls.get(), 0);
+    }
+}
@@ -0,0 +1,222 @@
+use core::ops::{Range, RangeInclusive};
+
+use crate::{RandomExt, RandomSource};
+
+/// A type that can be made from random bits
+///
+/// Integers and `bool`s are uniform over every value, floats are uniform in `[0, 1)`.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{Random, Sample};
+///
+/// // Get a random u16 and f32
+/// let mut r = Random::new(1234);
+/// let i = u16::sample(&mut r);
+/// let f = f32::sample(&mut r);
+/// assert!(f < 1.0);
+/// ```
+pub trait Sample: Sized {
+    /// Make a random value with bits from `r`
+    fn sample<R: RandomSource + ?Sized>(r: &mut R) -> Self;
+}
+
+/// A range that a random value can be picked from
+///
+/// Implemented for `start..end` and `start..=end` of every integer type,
+/// and for `start..end` of `f32` and `f64`.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{Random, SampleRange};
+///
+/// // Roll a die
+/// let mut r = Random::new(1234);
+/// let i = (1..=6).sample(&mut r);
+/// assert!((1..=6).contains(&i));
+/// ```
+pub trait SampleRange<T> {
+    /// Pick a value from the range with bits from `r`
+    ///
+    /// ## Panics
+    /// If the range is empty, or a float range has a bound that is not finite
+    fn sample<R: RandomSource + ?Sized>(self, r: &mut R) -> T;
+}
+
+/// Define [`Sample`] for types that are just the bits of a [`RandomExt`] method
+macro_rules! sample_bits {
+    ($($t:ty => $next:ident),* $(,)?) => {
+        $(
+            impl Sample for $t {
+                fn sample<R: RandomSource + ?Sized>(r: &mut R) -> $t {
+                    r.$next() as $t
+                }
+            }
+        )*
+    };
+}
+
+sample_bits!(
+    u8 => next_u32,
+    u16 => next_u32,
+    u32 => next_u32,
+    u64 => next_u64,
+    u128 => next_u128,
+    usize => next_usize,
+    i8 => next_u32,
+    i16 => next_u32,
+    i32 => next_u32,
+    i64 => next_u64,
+    i128 => next_u128,
+    isize => next_usize,
+    bool => next_bool,
+    f32 => next_f32,
+    f64 => next_f64,
+);
+
+/// Define [`SampleRange`] for both integer ranges with a `next_int_*` method
+macro_rules! sample_int_range {
+    ($($t:ty => $next:ident),* $(,)?) => {
+        $(
+            impl SampleRange<$t> for Range<$t> {
+                fn sample<R: RandomSource + ?Sized>(self, r: &mut R) -> $t {
+                    assert!(self.start < self.end, "range must not be empty");
+                    r.$next(self.start, self.end - 1)
+                }
+            }
+
+            impl SampleRange<$t> for RangeInclusive<$t> {
+                fn sample<R: RandomSource + ?Sized>(self, r: &mut R) -> $t {
+                    let (start, end) = self.into_inner();
+                    assert!(start <= end, "range must not be empty");
+                    r.$next(start, end)
+                }
+            }
+        )*
+    };
+}
+
+sample_int_range!(
+    u8 => next_int_u8,
+    u16 => next_int_u16,
+    u32 => next_int_u32,
+    u64 => next_int_u64,
+    u128 => next_int_u128,
+    usize => next_int_usize,
+    i8 => next_int_i8,
+    i16 => next_int_i16,
+    i32 => next_int_i32,
+    i64 => next_int_i64,
+    i128 => next_int_i128,
+    isize => next_int_isize,
+);
+
+/// Define [`SampleRange`] for half open float ranges
+///
+/// The value is interpolated as `start * (1 - u) + end * u`, which can't overflow
+/// even when `end - start` would, like for `MIN..MAX`.
+/// Rounding can still land on `end` (or just outside the range), so those rare values are drawn again.
+macro_rules! sample_float_range {
+    ($($t:ty => $next:ident),* $(,)?) => {
+        $(
+            impl SampleRange<$t> for Range<$t> {
+                fn sample<R: RandomSource + ?Sized>(self, r: &mut R) -> $t {
+                    assert!(self.start < self.end, "range must not be empty");
+                    assert!(
+                        self.start.is_finite() && self.end.is_finite(),
+                        "range bounds must be finite"
+                    );
+                    loop {
+                        let u = r.$next();
+                        let i = self.start * (1.0 - u) + self.end * u;
+                        if self.start <= i && i < self.end {
+                            return i;
+                        }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+sample_float_range!(f32 => next_f32, f64 => next_f64);
+
+#[cfg(test)]
+mod tests {
+    use super::{Sample, SampleRange};
+    use crate::{Random, RandomExt, RandomSource, SplitMix64};
+
+    #[test]
+    fn test_sample_bits() {
+        let mut a = SplitMix64::new(1234);
+        let mut b = SplitMix64::new(1234);
+        assert_eq!(u64::sample(&mut a), b.next_u64());
+        assert_eq!(i32::sample(&mut a), b.next_u32() as i32);
+        assert_eq!(u8::sample(&mut a), b.next_u32() as u8);
+        assert_eq!(u128::sample(&mut a), b.next_u128());
+        assert_eq!(bool::sample(&mut a), b.next_bool());
+        assert_eq!(f64::sample(&mut a), b.next_f64());
+    }
+
+    #[test]
+    fn test_int_range() {
+        let mut r = Random::new(1234);
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let i = (1..7).sample(&mut r);
+            assert!((1..7).contains(&i));
+            seen[i as usize - 1] = true;
+
+            let i = (-3_i8..=3).sample(&mut r);
+            assert!((-3..=3).contains(&i));
+        }
+        assert_eq!(seen, [true; 6]);
+
+        assert_eq!((5_u32..6).sample(&mut r), 5);
+        assert_eq!((5_u32..=5).sample(&mut r), 5);
+        (0..=u64::MAX).sample(&mut r);
+    }
+
+    #[test]
+    fn test_float_range() {
+        let mut r = Random::new(1234);
+        for _ in 0..1000 {
+            let i = (-2.5..4.0).sample(&mut r);
+            assert!((-2.5..4.0).contains(&i));
+
+            let i = (1.0_f32..1.5).sample(&mut r);
+            assert!((1.0..1.5).contains(&i));
+        }
+    }
+
+    #[test]
+    fn test_wide_float_range() {
+        let mut r = Random::new(1234);
+        let mut negative = false;
+        for _ in 0..1000 {
+            let i = (f64::MIN..f64::MAX).sample(&mut r);
+            assert!(i.is_finite());
+            negative |= i < 0.0;
+
+            let i = (f32::MIN..f32::MAX).sample(&mut r);
+            assert!(i.is_finite());
+
+            let i = (-1e308..1e308).sample(&mut r);
+            assert!((-1e308..1e308).contains(&i));
+        }
+        assert!(negative);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_infinite_range() {
+        (0.0..f64::INFINITY).sample(&mut Random::new(1234));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_empty_range() {
+        #[allow(clippy::reversed_empty_ranges)]
+        (5..5).sample(&mut Random::new(1234));
+    }
+}
@@ -0,0 +1,183 @@
+#[cfg(feature = "getrandom")]
+use crate::error::EntropyError;
+use crate::{RandomSource, SplitMix64};
+
+/// A generator that can be made from seed bytes
+///
+/// Every generator takes its own [`Seed`](SeedableRandom::Seed) with exactly as many bytes as it needs,
+/// and [`SeedableRandom::seed_from_u64`] can make one of those from a single `u64`.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{RandomSource, SeedableRandom, Xoshiro256StarStar};
+///
+/// // Make a generator with 256 bits of state from a single u64
+/// let mut r = Xoshiro256StarStar::seed_from_u64(1234);
+/// let i = r.next_u64();
+/// ```
+pub trait SeedableRandom: Sized {
+    /// The seed bytes, usually a byte array
+    type Seed: Default + AsRef<[u8]> + AsMut<[u8]>;
+
+    /// Make a new generator from seed bytes
+    ///
+    /// Multi byte values are read from the seed in little endian order.
+    fn from_seed(seed: Self::Seed) -> Self;
+
+    /// Make a new generator from a `u64`
+    ///
+    /// By default the seed is expanded with [`SplitMix64`], filling the seed bytes
+    /// with its outputs in little endian order, so similar `u64`s still give very different seeds.
+    fn seed_from_u64(seed: u64) -> Self {
+        let mut bytes = Self::Seed::default();
+        SplitMix64::new(seed).fill_bytes(bytes.as_mut());
+        Self::from_seed(bytes)
+    }
+
+    /// Make a new generator from a byte string
+    ///
+    /// The bytes are hashed with 64 bit FNV-1a, and the hash is expanded into the seed bytes
+    /// with [`SplitMix64`] outputs in little endian order.
+    /// This is fixed and the same on every platform, so a byte string always gives the same stream.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{RandomSource, SeedableRandom, Sfc64};
+    ///
+    /// // Make a generator from a file hash
+    /// let mut r = Sfc64::from_seed_bytes(&[0x3a, 0x91, 0x07, 0xc4]);
+    /// let i = r.next_u64();
+    /// ```
+    fn from_seed_bytes(bytes: &[u8]) -> Self {
+        let mut seed = Self::Seed::default();
+        SplitMix64::new(fnv1a(bytes)).fill_bytes(seed.as_mut());
+        Self::from_seed(seed)
+    }
+
+    /// Make a new generator from a string
+    ///
+    /// Same as [`SeedableRandom::from_seed_bytes`] with the UTF-8 bytes of the string.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Random, SeedableRandom};
+    ///
+    /// // Make a generator from a level seed
+    /// let mut r = Random::from_seed_str("forest-42");
+    /// let i = r.next_f64();
+    /// ```
+    fn from_seed_str(seed: &str) -> Self {
+        Self::from_seed_bytes(seed.as_bytes())
+    }
+
+    /// Make a new generator seeded from the OS
+    ///
+    /// The seed bytes are filled with [`fill_entropy`](crate::fill_entropy),
+    /// so every call gives a different stream.
+    /// Needs the `getrandom` feature.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use micro_rand::{Random, SeedableRandom};
+    ///
+    /// // Make a generator that is different every run
+    /// let mut r = Random::from_entropy().unwrap();
+    /// let i = r.next_f64();
+    /// ```
+    #[cfg(feature = "getrandom")]
+    fn from_entropy() -> Result<Self, EntropyError> {
+        let mut seed = Self::Seed::default();
+        crate::fill_entropy(seed.as_mut())?;
+        Ok(Self::from_seed(seed))
+    }
+
+    /// Mix fresh entropy into the generator
+    ///
+    /// By default the new seed is the generator's own output xored with `entropy`,
+    /// so the old state is mixed in and a weak source can't make the generator worse than it was.
+    /// Generators with a reseed of their own, like the DRBGs, run that instead.
+    /// This is what [`Reseeding`](crate::Reseeding) calls.
+    fn reseed_with(&mut self, entropy: Self::Seed)
+    where
+        Self: RandomSource,
+    {
+        let mut seed = Self::Seed::default();
+        self.fill_bytes(seed.as_mut());
+        for (i, j) in seed.as_mut().iter_mut().zip(entropy.as_ref()) {
+            *i ^= j;
+        }
+        *self = Self::from_seed(seed);
+    }
+}
+
+/// 64 bit FNV-1a hash
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for &i in bytes {
+        hash ^= i as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fnv1a, SeedableRandom};
+    use crate::{Random, RandomSource, SplitMix64, Xoshiro256StarStar};
+
+    #[test]
+    fn test_fnv1a() {
+        // From the FNV reference test vectors
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn test_seed_str() {
+        // FNV-1a of "forest-42" is 0x2e434e65ae73a5d1, and it expands to 0xd318f360ee7a8173, ...
+        let mut r = Random::from_seed_str("forest-42");
+        let expected = [3454602750, 2409795217, 420928634, 753652032, 119844985];
+        for i in expected.iter() {
+            assert_eq!(r.next_u32(), *i);
+        }
+        let mut a = Random::from_seed_str("forest-42");
+        let mut b = Random::new(0xd318f360ee7a8173_u64 as i64);
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        let mut r = Xoshiro256StarStar::from_seed_str("forest-42");
+        let expected = [
+            0x9849153e122441ae,
+            0x18033b75368d71c4,
+            0x92409283e0f621c4,
+            0xab917e6d2d0f03a7,
+            0xed04dfcf0211b6df,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u64(), *i);
+        }
+
+        // SplitMix64 takes the first expanded word as its state, unlike its seed_from_u64
+        let mut r = SplitMix64::from_seed_str("forest-42");
+        let expected = [
+            0xcb62a05401c8a957,
+            0xc6f697afc1c47707,
+            0x789452151ccbb6c7,
+            0x7e4ef639002a28d3,
+            0x186d293bed186d66,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u64(), *i);
+        }
+    }
+
+    #[test]
+    fn test_seed_bytes() {
+        let mut a = Xoshiro256StarStar::from_seed_str("forest-42");
+        let mut b = Xoshiro256StarStar::from_seed_bytes(b"forest-42");
+        assert_eq!(a.next_u64(), b.next_u64());
+
+        let mut a = Xoshiro256StarStar::from_seed_str("forest-43");
+        assert_ne!(a.next_u64(), b.next_u64());
+    }
+}
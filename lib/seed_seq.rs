@@ -0,0 +1,339 @@
+use crate::SeedableRandom;
+
+const POOL_SIZE: usize = 4;
+const INIT_A: u32 = 0x43b0_d7e5;
+const MULT_A: u32 = 0x931e_8875;
+const INIT_B: u32 = 0x8b51_f9dd;
+const MULT_B: u32 = 0x58f3_8ded;
+const MIX_MULT_L: u32 = 0xca01_f9dd;
+const MIX_MULT_R: u32 = 0x4973_f715;
+const XSHIFT: u32 = 16;
+
+/// Seed Sequence
+///
+/// Mixes any amount of entropy into a pool of 128 bits with a hash,
+/// then expands the pool into as many well mixed seed words as needed.
+/// Child sequences can be spawned from it for parallel work, each with its own spawn key,
+/// and their children can be spawned in turn.
+///
+/// This is the same algorithm as NumPy's `SeedSequence`, and gives the same words:
+/// `SeedSequence::new(&[12345]).child(3)` matches `SeedSequence(12345, spawn_key=(3,))`.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{RandomSource, SeedSequence, Xoshiro256StarStar};
+///
+/// // Make an independent generator for each of 4 workers
+/// let mut seq = SeedSequence::from_u128(0x8c3c_010c_b4c5_8d5b_12f1_7ba4_a8f8_8b3e);
+/// let mut workers = [
+///     seq.spawn().generator::<Xoshiro256StarStar>(),
+///     seq.spawn().generator::<Xoshiro256StarStar>(),
+///     seq.spawn().generator::<Xoshiro256StarStar>(),
+///     seq.spawn().generator::<Xoshiro256StarStar>(),
+/// ];
+/// let i = workers[3].next_u64();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SeedSequence {
+    pool: [u32; POOL_SIZE],
+    hash_const: u32,
+    spawned: u32,
+}
+
+impl SeedSequence {
+    /// Make a new seed sequence from entropy words
+    pub fn new(entropy: &[u32]) -> SeedSequence {
+        SeedSequence::from_words(entropy.iter().copied())
+    }
+
+    /// Make a new seed sequence from an integer
+    ///
+    /// Like a Python `int` in NumPy, this is split into 32 bit words, low word first.
+    pub fn from_u128(entropy: u128) -> SeedSequence {
+        SeedSequence::from_words((0..4).map(|i| (entropy >> (32 * i)) as u32))
+    }
+
+    /// Make a new seed sequence from 64 bit entropy words
+    ///
+    /// Like a `np.uint64` array in NumPy, each word is split in two, low half first.
+    pub fn from_u64s(entropy: &[u64]) -> SeedSequence {
+        SeedSequence::from_words(entropy.iter().flat_map(|&i| [i as u32, (i >> 32) as u32]))
+    }
+
+    /// Make a new seed sequence from entropy bytes
+    ///
+    /// The bytes are read as little endian 32 bit words, with the last one padded with zeros.
+    pub fn from_bytes(entropy: &[u8]) -> SeedSequence {
+        SeedSequence::from_words(entropy.chunks(4).map(|i| {
+            let mut word = [0; 4];
+            word[..i.len()].copy_from_slice(i);
+            u32::from_le_bytes(word)
+        }))
+    }
+
+    /// Get the child sequence with spawn key `index`
+    ///
+    /// This does not count towards [`SeedSequence::spawn`],
+    /// so it can be used to go straight to a known child.
+    pub fn child(&self, index: u32) -> SeedSequence {
+        let mut child = SeedSequence {
+            pool: self.pool,
+            hash_const: self.hash_const,
+            spawned: 0,
+        };
+        child.absorb(index);
+        child
+    }
+
+    /// Spawn the next child sequence
+    ///
+    /// The `n`th call gives [`SeedSequence::child`]`(n)`, counting from 0.
+    ///
+    /// ## Panics
+    /// If `u32::MAX` children have already been spawned, as the count would overflow.
+    /// The last child can still be had with [`SeedSequence::child`]`(u32::MAX)`.
+    pub fn spawn(&mut self) -> SeedSequence {
+        let child = self.child(self.spawned);
+        self.spawned = self
+            .spawned
+            .checked_add(1)
+            .expect("too many children spawned");
+        child
+    }
+
+    /// Get the number of children spawned so far
+    pub fn children_spawned(&self) -> u32 {
+        self.spawned
+    }
+
+    /// Fill a buffer with seed words
+    ///
+    /// The words only depend on the sequence, so asking again gives the same words.
+    pub fn generate_state(&self, out: &mut [u32]) {
+        for (i, j) in out.iter_mut().zip(self.words()) {
+            *i = j;
+        }
+    }
+
+    /// Fill a buffer with 64 bit seed words
+    ///
+    /// Each is made from two 32 bit words, low half first,
+    /// like `generate_state(n, np.uint64)` in NumPy.
+    pub fn generate_state_u64(&self, out: &mut [u64]) {
+        let mut words = self.words();
+        for i in out.iter_mut() {
+            let lo = words.next().unwrap() as u64;
+            *i = lo | (words.next().unwrap() as u64) << 32;
+        }
+    }
+
+    /// Fill a buffer with seed bytes
+    ///
+    /// These are the seed words in little endian order.
+    pub fn fill_bytes(&self, out: &mut [u8]) {
+        for (i, j) in out.chunks_mut(4).zip(self.words()) {
+            i.copy_from_slice(&j.to_le_bytes()[..i.len()]);
+        }
+    }
+
+    /// Make a generator, seeded with [`SeedSequence::fill_bytes`]
+    pub fn generator<G: SeedableRandom>(&self) -> G {
+        let mut seed = G::Seed::default();
+        self.fill_bytes(seed.as_mut());
+        G::from_seed(seed)
+    }
+
+    /// Expand the pool into an endless run of seed words
+    fn words(&self) -> impl Iterator<Item = u32> + '_ {
+        let mut hash_const = INIT_B;
+        self.pool.iter().cycle().map(move |i| {
+            let mut x = i ^ hash_const;
+            hash_const = hash_const.wrapping_mul(MULT_B);
+            x = x.wrapping_mul(hash_const);
+            x ^ x >> XSHIFT
+        })
+    }
+
+    /// Mix the first words into the pool, then fold in the rest one by one
+    fn from_words(words: impl IntoIterator<Item = u32>) -> SeedSequence {
+        let mut words = words.into_iter();
+        let mut hash_const = INIT_A;
+        let mut pool = [0; POOL_SIZE];
+        for i in pool.iter_mut() {
+            *i = hashmix(words.next().unwrap_or(0), &mut hash_const);
+        }
+        for src in 0..POOL_SIZE {
+            for dst in 0..POOL_SIZE {
+                if src != dst {
+                    let h = hashmix(pool[src], &mut hash_const);
+                    pool[dst] = mix(pool[dst], h);
+                }
+            }
+        }
+
+        let mut seq = SeedSequence {
+            pool,
+            hash_const,
+            spawned: 0,
+        };
+        for i in words {
+            seq.absorb(i);
+        }
+        seq
+    }
+
+    /// Fold one more word into the pool
+    fn absorb(&mut self, word: u32) {
+        for i in 0..POOL_SIZE {
+            let h = hashmix(word, &mut self.hash_const);
+            self.pool[i] = mix(self.pool[i], h);
+        }
+    }
+}
+
+fn hashmix(value: u32, hash_const: &mut u32) -> u32 {
+    let mut x = value ^ *hash_const;
+    *hash_const = hash_const.wrapping_mul(MULT_A);
+    x = x.wrapping_mul(*hash_const);
+    x ^ x >> XSHIFT
+}
+
+fn mix(x: u32, y: u32) -> u32 {
+    let r = MIX_MULT_L
+        .wrapping_mul(x)
+        .wrapping_sub(MIX_MULT_R.wrapping_mul(y));
+    r ^ r >> XSHIFT
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryInto;
+
+    use super::SeedSequence;
+    use crate::{Pcg32, RandomSource, SeedableRandom};
+
+    fn state(seq: &SeedSequence) -> [u32; 4] {
+        let mut out = [0; 4];
+        seq.generate_state(&mut out);
+        out
+    }
+
+    #[test]
+    fn test_reference() {
+        // From NumPy's SeedSequence reference data
+        let seq = SeedSequence::new(&[3735928559, 195939070, 229505742, 305419896]);
+        assert_eq!(state(&seq), [3914649087, 576849849, 3593928901, 2229911004]);
+    }
+
+    #[test]
+    fn test_numpy() {
+        // Worked out with NumPy's algorithm, for SeedSequence(12345).generate_state(8)
+        let seq = SeedSequence::new(&[12345]);
+        let mut out = [0; 8];
+        seq.generate_state(&mut out);
+        assert_eq!(
+            out,
+            [
+                2688385916, 3048105090, 4196366895, 3152189807, 924159892, 1692637855, 2685664627,
+                1052446614
+            ]
+        );
+
+        // More than 4 words of entropy
+        let seq = SeedSequence::new(&[0x89abcdef, 0x01234567, 0xfedcba98, 0x76543210, 7]);
+        assert_eq!(
+            state(&seq),
+            [0xab36dec9, 0xd3d4a20a, 0x3a6f7010, 0x4273c460]
+        );
+
+        // Spawn keys (0,), (1,) and (0, 1)
+        let seq = SeedSequence::new(&[12345]);
+        assert_eq!(
+            state(&seq.child(0)),
+            [959183449, 3196577012, 2719720162, 1792540688]
+        );
+        assert_eq!(
+            state(&seq.child(1)),
+            [1457248422, 358904087, 711457119, 482272698]
+        );
+        assert_eq!(
+            state(&seq.child(0).child(1)),
+            [3776034388, 2666190566, 3527492146, 3660274090]
+        );
+    }
+
+    #[test]
+    fn test_entropy_forms() {
+        let a = state(&SeedSequence::new(&[
+            0x89abcdef, 0x01234567, 0xfedcba98, 0x76543210, 7,
+        ]));
+        let b = SeedSequence::from_u64s(&[0x01234567_89abcdef, 0x76543210_fedcba98, 7]);
+        let c = SeedSequence::from_bytes(&[
+            0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01, 0x98, 0xba, 0xdc, 0xfe, 0x10, 0x32,
+            0x54, 0x76, 0x07,
+        ]);
+        assert_eq!(a, state(&c));
+
+        // The last 64 bit word brings a zero high half along
+        let a = state(&SeedSequence::new(&[
+            0x89abcdef, 0x01234567, 0xfedcba98, 0x76543210, 7, 0,
+        ]));
+        assert_eq!(a, state(&b));
+
+        // Short entropy is padded with zeros
+        assert_eq!(
+            state(&SeedSequence::from_u128(12345)),
+            state(&SeedSequence::new(&[12345]))
+        );
+        assert_eq!(
+            state(&SeedSequence::new(&[12345, 0, 0, 0])),
+            state(&SeedSequence::new(&[12345]))
+        );
+        assert_eq!(
+            state(&SeedSequence::from_bytes(&[])),
+            state(&SeedSequence::new(&[0]))
+        );
+    }
+
+    #[test]
+    fn test_spawn() {
+        let mut seq = SeedSequence::new(&[12345]);
+        for i in 0..5 {
+            assert_eq!(state(&seq.spawn()), state(&seq.child(i)));
+        }
+        assert_eq!(seq.children_spawned(), 5);
+        assert_ne!(state(&seq.child(0)), state(&seq.child(1)));
+        assert_ne!(state(&seq.child(0)), state(&seq));
+    }
+
+    #[test]
+    #[should_panic(expected = "too many children spawned")]
+    fn test_spawn_overflow() {
+        let mut seq = SeedSequence::new(&[12345]);
+        seq.spawned = u32::MAX;
+        seq.spawn();
+    }
+
+    #[test]
+    fn test_output_forms() {
+        let seq = SeedSequence::new(&[12345]);
+        let mut words = [0; 40];
+        seq.generate_state(&mut words);
+
+        let mut wide = [0; 20];
+        seq.generate_state_u64(&mut wide);
+        for (i, j) in wide.iter().zip(words.chunks_exact(2)) {
+            assert_eq!(*i, j[0] as u64 | (j[1] as u64) << 32);
+        }
+
+        let mut bytes = [0; 159];
+        seq.fill_bytes(&mut bytes);
+        for (i, j) in bytes.chunks(4).zip(words.iter()) {
+            assert_eq!(i, &j.to_le_bytes()[..i.len()]);
+        }
+
+        let mut a: Pcg32 = seq.generator();
+        let mut b = Pcg32::from_seed(bytes[..16].try_into().unwrap());
+        assert_eq!(a.next_u32(), b.next_u32());
+    }
+}
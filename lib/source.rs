@@ -0,0 +1,144 @@
+/// A source of random bits
+///
+/// This is the only thing a generator has to implement,
+/// all the convenience methods come from [`RandomExt`](crate::RandomExt) on top of it.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{Random, RandomExt, RandomSource};
+///
+/// // Works with any generator
+/// fn roll<R: RandomSource>(r: &mut R) -> u8 {
+///     r.next_int_u8(1, 6)
+/// }
+///
+/// let mut r = Random::new(1234);
+/// let i = roll(&mut r);
+/// ```
+pub trait RandomSource {
+    /// Get the next u32, with every bit uniform
+    fn next_u32(&mut self) -> u32;
+
+    /// Get the next u64, with every bit uniform
+    fn next_u64(&mut self) -> u64;
+
+    /// Fill a buffer with random bytes
+    ///
+    /// By default this takes one [`RandomSource::next_u64`] for every 8 bytes (or part of 8 bytes),
+    /// in little endian order.
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    /// Get the next float 64 in `[0, 1)`
+    ///
+    /// By default this uses the top 53 bits of [`RandomSource::next_u64`].
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// Get the next float 32 in `[0, 1)`
+    ///
+    /// By default this uses the top 24 bits of [`RandomSource::next_u32`].
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1_u32 << 24) as f32
+    }
+}
+
+impl<R: RandomSource + ?Sized> RandomSource for &mut R {
+    fn next_u32(&mut self) -> u32 {
+        (**self).next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (**self).next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        (**self).fill_bytes(dest)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (**self).next_f64()
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        (**self).next_f32()
+    }
+}
+
+/// A marker for generators that are cryptographically secure
+///
+/// Their output can't be predicted from earlier output without knowing the seed,
+/// so they can be used for keys, tokens and nonces.
+/// [`Random`](crate::Random) and the other small generators are **not** secure,
+/// as their state can be worked out from just a few outputs.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{CryptoRandom, RandomSource};
+///
+/// // Only accept secure generators
+/// fn make_token<R: CryptoRandom>(r: &mut R) -> [u8; 16] {
+///     let mut token = [0; 16];
+///     r.fill_bytes(&mut token);
+///     token
+/// }
+/// ```
+pub trait CryptoRandom: RandomSource {}
+
+impl<R: CryptoRandom + ?Sized> CryptoRandom for &mut R {}
+
+#[cfg(test)]
+mod tests {
+    use super::RandomSource;
+
+    /// Counts up from 0, one per call
+    struct Counter(u64);
+
+    impl RandomSource for Counter {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let out = self.0;
+            self.0 = self.0.wrapping_add(1);
+            out
+        }
+    }
+
+    #[test]
+    fn test_fill_bytes() {
+        let mut r = Counter(0x0102_0304_0506_0708);
+        let mut buf = [0; 11];
+        r.fill_bytes(&mut buf);
+        assert_eq!(buf, [8, 7, 6, 5, 4, 3, 2, 1, 9, 7, 6]);
+        assert_eq!(r.0, 0x0102_0304_0506_070a);
+    }
+
+    #[test]
+    fn test_next_float() {
+        let mut r = Counter(u64::MAX);
+        assert_eq!(r.next_f64(), 1.0 - 1.0 / (1_u64 << 53) as f64);
+        assert_eq!(r.next_f32(), 0.0);
+
+        let mut r = Counter(1 << 63);
+        assert_eq!(r.next_f64(), 0.5);
+        assert_eq!(r.next_f32(), 0.0);
+    }
+
+    #[test]
+    fn test_mut_ref() {
+        fn take<R: RandomSource>(mut r: R) -> u64 {
+            r.next_u64()
+        }
+
+        let mut r = Counter(5);
+        assert_eq!(take(&mut r), 5);
+        assert_eq!(take(&mut r), 6);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{RandomSource, SeedableRandom};
+
+/// A generator that can hand out independent generators for parallel work
+///
+/// Each call of [`SplitRandom::fork`] makes a new generator and moves this one on,
+/// so the children only depend on the parent's state and the order of the forks,
+/// not on which thread runs first.
+/// Generators with jump functions (LCGs, PCG and xoshiro) give each child its own
+/// block of the parent's stream, the rest seed the child from the parent's output.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{RandomSource, SplitRandom, Xoshiro256StarStar};
+///
+/// // Make a generator for each of 4 workers
+/// let mut parent = Xoshiro256StarStar::new([1, 2, 3, 4]);
+/// let mut workers = [
+///     parent.fork(),
+///     parent.fork(),
+///     parent.fork(),
+///     parent.fork(),
+/// ];
+/// let i = workers[2].next_u64();
+/// ```
+pub trait SplitRandom: RandomSource + Sized {
+    /// Make a new generator, and move this one past it
+    fn fork(&mut self) -> Self;
+}
+
+/// Seed a new generator with bytes from `parent`
+pub(crate) fn fork_from_seed<G: SeedableRandom, R: RandomSource>(parent: &mut R) -> G {
+    let mut seed = G::Seed::default();
+    parent.fill_bytes(seed.as_mut());
+    G::from_seed(seed)
+}
+
+/// Leapfrog adapter
+///
+/// Worker `i` of `n` takes every `n`th value of a generator, starting from value `i`,
+/// so `n` workers together get every value of the stream exactly once.
+/// Each value is one [`RandomSource::next_u64`] of the wrapped generator,
+/// and the values in between are thrown away, so a step costs `n` steps of the generator.
+///
+/// For [`Random`](crate::Random), [`Random::leapfrog`](crate::Random::leapfrog) does the same
+/// without throwing any values away.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{Leapfrog, RandomSource, SplitMix64};
+///
+/// // Worker 1 of 3 gets values 1, 4, 7, ...
+/// let mut r = Leapfrog::new(SplitMix64::new(1234), 1, 3);
+/// let i = r.next_u64();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Leapfrog<G> {
+    generator: G,
+    n: u64,
+}
+
+impl<G: RandomSource> Leapfrog<G> {
+    /// Make a new leapfrog stream for worker `i` of `n`
+    ///
+    /// ## Panics
+    /// If `n` is 0 or `i` is not less than `n`.
+    pub fn new(mut generator: G, i: u64, n: u64) -> Leapfrog<G> {
+        assert!(
+            i < n,
+            "worker index must be less than the number of workers"
+        );
+        for _ in 0..i {
+            generator.next_u64();
+        }
+        Leapfrog { generator, n }
+    }
+}
+
+impl<G: RandomSource> RandomSource for Leapfrog<G> {
+    /// The high 32 bits of [`RandomSource::next_u64`]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let out = self.generator.next_u64();
+        for _ in 1..self.n {
+            self.generator.next_u64();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Leapfrog, SplitRandom};
+    use crate::{Pcg32, RandomSource, Sfc64, SplitMix64, Xoshiro256StarStar};
+
+    #[test]
+    fn test_leapfrog_union() {
+        // Taking turns between the workers gives back the original stream
+        for n in 1..6 {
+            let mut r = SplitMix64::new(1234);
+            let mut workers =
+                [0, 1, 2, 3, 4].map(|i| Leapfrog::new(SplitMix64::new(1234), i % n, n));
+            for _ in 0..100 {
+                for w in workers.iter_mut().take(n as usize) {
+                    assert_eq!(w.next_u64(), r.next_u64());
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_leapfrog_bad_index() {
+        Leapfrog::new(SplitMix64::new(1234), 3, 3);
+    }
+
+    #[test]
+    fn test_fork_jump() {
+        // The first child takes over the parent's stream, and the parent jumps
+        let mut parent = Xoshiro256StarStar::new([1, 2, 3, 4]);
+        let mut child = parent.fork();
+        let mut r = Xoshiro256StarStar::new([1, 2, 3, 4]);
+        assert_eq!(child.next_u64(), r.next_u64());
+
+        let mut r = Xoshiro256StarStar::new([1, 2, 3, 4]);
+        r.jump();
+        assert_eq!(parent.next_u64(), r.next_u64());
+
+        let mut parent = Pcg32::new(42, 54);
+        let mut child = parent.fork();
+        let mut r = Pcg32::new(42, 54);
+        assert_eq!(child.next_u32(), r.next_u32());
+        r.advance((1 << 48) - 1);
+        assert_eq!(parent.next_u32(), r.next_u32());
+    }
+
+    #[test]
+    fn test_fork_deterministic() {
+        let mut a = Sfc64::new(1234);
+        let mut b = Sfc64::new(1234);
+        let mut a = [a.fork(), a.fork()];
+        let mut b = [b.fork(), b.fork()];
+        assert_eq!(a[1].next_u64(), b[1].next_u64());
+        assert_eq!(a[0].next_u64(), b[0].next_u64());
+        assert_ne!(a[0].next_u64(), a[1].next_u64());
+    }
+}
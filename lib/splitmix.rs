@@ -0,0 +1,120 @@
+use crate::{RandomSource, SeedableRandom};
+
+/// SplitMix64 Generator
+///
+/// A very fast generator with 64 bits of state, that passes through every `u64` exactly once.
+/// Each output is the state after adding a constant, run through a strong mixing function,
+/// so even seeds with only a few bits set give well mixed output.
+///
+/// This is what [`SeedableRandom::seed_from_u64`] uses to turn one `u64`
+/// into as many seed bytes as a generator needs.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{RandomSource, SplitMix64};
+///
+/// // Make a new generator with seed 1234
+/// let mut r = SplitMix64::new(1234);
+/// let i = r.next_u64();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Make a new SplitMix64 generator
+    ///
+    /// Every seed is valid, including 0.
+    pub const fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+}
+
+impl Default for SplitMix64 {
+    /// Same as `SplitMix64::new(0)`
+    fn default() -> SplitMix64 {
+        SplitMix64::new(0)
+    }
+}
+
+impl RandomSource for SplitMix64 {
+    /// The high 32 bits of [`RandomSource::next_u64`]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ z >> 30).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ z >> 27).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ z >> 31
+    }
+}
+
+impl SeedableRandom for SplitMix64 {
+    type Seed = [u8; 8];
+
+    /// The seed is the state, as a little endian `u64`
+    fn from_seed(seed: [u8; 8]) -> SplitMix64 {
+        SplitMix64::new(u64::from_le_bytes(seed))
+    }
+
+    /// Same as [`SplitMix64::new`], the seed is not expanded first
+    fn seed_from_u64(seed: u64) -> SplitMix64 {
+        SplitMix64::new(seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SplitMix64;
+    use crate::{RandomSource, SeedableRandom};
+
+    #[test]
+    fn test_known_answer() {
+        // From the reference splitmix64.c
+        let mut r = SplitMix64::new(1477776061723855037);
+        let expected = [
+            1985237415132408290,
+            2979275885539914483,
+            13511426838097143398,
+            8488337342461049707,
+            15141737807933549159,
+            17093170987380407015,
+            16389528042912955399,
+            13177319091862933652,
+            10841969400225389492,
+            17094824097954834098,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u64(), *i);
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        let mut a = SplitMix64::seed_from_u64(42);
+        let mut b = SplitMix64::from_seed(42_u64.to_le_bytes());
+        let mut c = SplitMix64::new(42);
+        for _ in 0..10 {
+            let i = a.next_u64();
+            assert_eq!(b.next_u64(), i);
+            assert_eq!(c.next_u64(), i);
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        // The seed material is just the outputs in little endian order
+        let mut r = SplitMix64::new(0);
+        let mut buf = [0; 20];
+        r.fill_bytes(&mut buf);
+
+        let mut r = SplitMix64::new(0);
+        assert_eq!(buf[..8], r.next_u64().to_le_bytes());
+        assert_eq!(buf[8..16], r.next_u64().to_le_bytes());
+        assert_eq!(buf[16..], r.next_u64().to_le_bytes()[..4]);
+    }
+}
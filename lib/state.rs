@@ -0,0 +1,54 @@
+use crate::error::StateError;
+use crate::seed::fnv1a;
+
+/// Magic bytes at the start of every saved state
+const MAGIC: [u8; 4] = *b"MRNG";
+
+/// The format version written by this crate
+const VERSION: u8 = 1;
+
+/// Length of the header before the algorithm's own fields
+pub(crate) const HEADER_LEN: usize = 8;
+
+/// Length of the checksum after the algorithm's own fields
+pub(crate) const CHECKSUM_LEN: usize = 8;
+
+/// Algorithm tag of [`Random`](crate::Random)
+pub(crate) const RANDOM: u8 = 1;
+
+/// Write the header and checksum around the fields already in `buf`
+///
+/// The header is the magic, the version, the algorithm tag and two zero bytes.
+/// The checksum is the 64 bit FNV-1a hash of everything before it, little endian.
+pub(crate) fn seal(buf: &mut [u8], tag: u8) {
+    buf[..4].copy_from_slice(&MAGIC);
+    buf[4] = VERSION;
+    buf[5] = tag;
+    buf[6..HEADER_LEN].fill(0);
+
+    let body = buf.len() - CHECKSUM_LEN;
+    let checksum = fnv1a(&buf[..body]);
+    buf[body..].copy_from_slice(&checksum.to_le_bytes());
+}
+
+/// Check the header and checksum of a saved state, and get the fields between them
+pub(crate) fn open(buf: &[u8], tag: u8, len: usize) -> Result<&[u8], StateError> {
+    if buf.len() < HEADER_LEN || buf[..4] != MAGIC {
+        return Err(StateError::BadMagic);
+    }
+    if buf[4] != VERSION {
+        return Err(StateError::UnsupportedVersion(buf[4]));
+    }
+    if buf[5] != tag {
+        return Err(StateError::WrongAlgorithm(buf[5]));
+    }
+    if buf.len() != len {
+        return Err(StateError::WrongLength);
+    }
+
+    let body = len - CHECKSUM_LEN;
+    if fnv1a(&buf[..body]).to_le_bytes() != buf[body..] {
+        return Err(StateError::ChecksumMismatch);
+    }
+    Ok(&buf[HEADER_LEN..body])
+}
@@ -0,0 +1,166 @@
+use core::cell::RefCell;
+use core::marker::PhantomData;
+
+use crate::{RandomSource, Sample, SampleRange, SeedableRandom, Xoshiro256StarStar};
+
+std::thread_local! {
+    static THREAD_RNG: RefCell<Option<Xoshiro256StarStar>> = const { RefCell::new(None) };
+}
+
+/// Run `f` on the thread local generator, seeding it from the OS if it has not been seeded yet
+fn with_rng<T>(f: impl FnOnce(&mut Xoshiro256StarStar) -> T) -> T {
+    THREAD_RNG.with(|r| {
+        let mut r = r.borrow_mut();
+        let rng = r.get_or_insert_with(|| {
+            Xoshiro256StarStar::from_entropy().expect("could not seed the thread generator")
+        });
+        f(rng)
+    })
+}
+
+/// Handle to the thread local generator
+///
+/// Every thread has its own [`Xoshiro256StarStar`], seeded from the OS the first time it is used.
+/// The handle is just a way to reach it, so it is free to make and can't be sent to other threads.
+/// Needs the `std` feature.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{thread_rng, RandomExt};
+///
+/// // Shuffle with the thread local generator
+/// let mut deck = [1, 2, 3, 4, 5];
+/// thread_rng().shuffle(&mut deck);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThreadRng {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl RandomSource for ThreadRng {
+    fn next_u32(&mut self) -> u32 {
+        with_rng(|r| r.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        with_rng(|r| r.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        with_rng(|r| r.fill_bytes(dest))
+    }
+}
+
+impl Default for ThreadRng {
+    /// Same as [`thread_rng`]
+    fn default() -> ThreadRng {
+        thread_rng()
+    }
+}
+
+/// Get a handle to the thread local generator
+///
+/// ## Panics
+/// The first use on each thread panics if the OS can't give any entropy,
+/// unless [`seed_thread_rng`] was called first.
+pub fn thread_rng() -> ThreadRng {
+    ThreadRng {
+        _not_send: PhantomData,
+    }
+}
+
+/// Reseed the thread local generator
+///
+/// Only the generator of the calling thread is reseeded, with [`SeedableRandom::seed_from_u64`],
+/// so a test can seed it first thing and get the same values every run.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{random, seed_thread_rng};
+///
+/// // Values are the same after seeding the same way
+/// seed_thread_rng(1234);
+/// let a = random::<u64>();
+/// seed_thread_rng(1234);
+/// assert_eq!(a, random::<u64>());
+/// ```
+pub fn seed_thread_rng(seed: u64) {
+    let rng = Xoshiro256StarStar::seed_from_u64(seed);
+    THREAD_RNG.with(|r| *r.borrow_mut() = Some(rng));
+}
+
+/// Get a random value from the thread local generator
+///
+/// See [`Sample`] for the types and how they are made.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::random;
+///
+/// // Get a float in [0, 1) and a coin flip
+/// let f = random::<f64>();
+/// let heads: bool = random();
+/// ```
+pub fn random<T: Sample>() -> T {
+    T::sample(&mut thread_rng())
+}
+
+/// Get a random value in a range from the thread local generator
+///
+/// ## Panics
+/// If the range is empty, or a float range has a bound that is not finite
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::range;
+///
+/// // Roll a die and pick an angle
+/// let roll = range(1..=6);
+/// let angle = range(0.0..360.0);
+/// ```
+pub fn range<T, R: SampleRange<T>>(range: R) -> T {
+    range.sample(&mut thread_rng())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{random, range, seed_thread_rng, thread_rng};
+    use crate::{RandomSource, SeedableRandom, Xoshiro256StarStar};
+
+    #[test]
+    fn test_seed_thread_rng() {
+        seed_thread_rng(1234);
+        let mut r = Xoshiro256StarStar::seed_from_u64(1234);
+        assert_eq!(random::<u64>(), r.next_u64());
+        assert_eq!(thread_rng().next_u32(), r.next_u32());
+
+        seed_thread_rng(1234);
+        let a = [random::<u32>(), range(0..100), range(5..=10)];
+        seed_thread_rng(1234);
+        let b = [random::<u32>(), range(0..100), range(5..=10)];
+        assert_eq!(a, b);
+
+        assert!(range(f64::MIN..f64::MAX).is_finite());
+    }
+
+    #[test]
+    fn test_threads_differ() {
+        let a = std::thread::spawn(random::<u64>).join().unwrap();
+        let b = std::thread::spawn(random::<u64>).join().unwrap();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_seed_is_per_thread() {
+        seed_thread_rng(1234);
+        let a = random::<u64>();
+        let b = std::thread::spawn(|| {
+            seed_thread_rng(1234);
+            random::<u64>()
+        })
+        .join()
+        .unwrap();
+        assert_eq!(a, b);
+        assert_ne!(std::thread::spawn(random::<u64>).join().unwrap(), a);
+    }
+}
@@ -0,0 +1,603 @@
+use core::convert::TryInto;
+use core::mem;
+
+use crate::error::ParamError;
+use crate::{RandomSource, SeedableRandom, SplitRandom};
+
+/// Define the constructors and jump functions shared by all the xoshiro generators
+///
+/// A jump polynomial is applied by stepping the generator once per bit of the polynomial,
+/// and xoring together the states that line up with the set bits.
+macro_rules! impl_xoshiro {
+    ($name:ident, $t:ty, $n:expr, $bytes:expr, $jump:expr, $long_jump:expr, $jump_doc:expr, $long_jump_doc:expr) => {
+        impl $name {
+            #[doc = concat!("Make a new ", stringify!($name), " generator from its state")]
+            ///
+            /// ## Panics
+            /// If the state is all zeros, as the generator would only ever output zero.
+            /// Use the `try_new` constructor to get an error instead.
+            pub const fn new(state: [$t; $n]) -> $name {
+                match $name::try_new(state) {
+                    Ok(i) => i,
+                    Err(_) => panic!("state must not be all zeros"),
+                }
+            }
+
+            #[doc = concat!("Make a new ", stringify!($name), " generator from its state, checking it first")]
+            ///
+            /// Returns [`ParamError::FixedPointSeed`] if the state is all zeros.
+            pub const fn try_new(state: [$t; $n]) -> Result<$name, ParamError> {
+                let mut i = 0;
+                while i < $n && state[i] == 0 {
+                    i += 1;
+                }
+                if i == $n {
+                    return Err(ParamError::FixedPointSeed);
+                }
+
+                Ok($name { s: state })
+            }
+
+            #[doc = concat!("Jump the generator forward by ", $jump_doc, " steps")]
+            ///
+            /// Can be used to make non overlapping streams for parallel work.
+            pub fn jump(&mut self) {
+                self.jump_with(&$jump);
+            }
+
+            #[doc = concat!("Jump the generator forward by ", $long_jump_doc, " steps")]
+            ///
+            /// Can be used to make separate starting points for sets of [`jump`](Self::jump) streams.
+            pub fn long_jump(&mut self) {
+                self.jump_with(&$long_jump);
+            }
+
+            fn jump_with(&mut self, poly: &[$t; $n]) {
+                let mut s = [0; $n];
+                for word in poly.iter() {
+                    for b in 0..<$t>::BITS {
+                        if word >> b & 1 == 1 {
+                            for (i, j) in s.iter_mut().zip(self.s.iter()) {
+                                *i ^= j;
+                            }
+                        }
+                        self.step();
+                    }
+                }
+                self.s = s;
+            }
+        }
+
+        impl SeedableRandom for $name {
+            type Seed = [u8; $bytes];
+
+            /// The seed is the state, as little endian words
+            ///
+            /// An all zero seed is replaced with `seed_from_u64(0)`.
+            fn from_seed(seed: [u8; $bytes]) -> $name {
+                let mut s = [0; $n];
+                for (i, j) in s.iter_mut().zip(seed.chunks_exact(mem::size_of::<$t>())) {
+                    *i = <$t>::from_le_bytes(j.try_into().unwrap());
+                }
+
+                match $name::try_new(s) {
+                    Ok(i) => i,
+                    Err(_) => $name::seed_from_u64(0),
+                }
+            }
+        }
+
+        impl Default for $name {
+            /// Same as `seed_from_u64(0)`, as the all zero state is not allowed
+            fn default() -> $name {
+                $name::seed_from_u64(0)
+            }
+        }
+
+        impl SplitRandom for $name {
+            #[doc = concat!("The child takes over the stream, and this generator jumps ", $jump_doc, " steps ahead")]
+            fn fork(&mut self) -> $name {
+                let child = $name { s: self.s };
+                self.jump();
+                child
+            }
+        }
+    };
+}
+
+/// Xoshiro256** Generator
+///
+/// An all purpose generator with 256 bits of state and a period of `2^256 - 1`.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{RandomSource, Xoshiro256StarStar};
+///
+/// // Make a new generator from its state
+/// let mut r = Xoshiro256StarStar::new([1, 2, 3, 4]);
+/// assert_eq!(r.next_u64(), 11520);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Xoshiro256StarStar {
+    s: [u64; 4],
+}
+
+/// Xoshiro256+ Generator
+///
+/// Like [`Xoshiro256StarStar`] but a bit faster.
+/// The lowest 3 bits of each output are weak, so it's best for making floats.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{RandomSource, Xoshiro256Plus};
+///
+/// // Make a new generator from its state
+/// let mut r = Xoshiro256Plus::new([1, 2, 3, 4]);
+/// assert_eq!(r.next_u64(), 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Xoshiro256Plus {
+    s: [u64; 4],
+}
+
+/// Xoshiro128** Generator
+///
+/// The 32 bit version of [`Xoshiro256StarStar`], with 128 bits of state and a period of `2^128 - 1`.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{RandomSource, Xoshiro128StarStar};
+///
+/// // Make a new generator from its state
+/// let mut r = Xoshiro128StarStar::new([1, 2, 3, 4]);
+/// assert_eq!(r.next_u32(), 11520);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Xoshiro128StarStar {
+    s: [u32; 4],
+}
+
+/// Xoroshiro128+ Generator
+///
+/// A small, fast generator with 128 bits of state and a period of `2^128 - 1`.
+/// The lowest bits of each output are weak, so it's best for making floats.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{RandomSource, Xoroshiro128Plus};
+///
+/// // Make a new generator from its state
+/// let mut r = Xoroshiro128Plus::new([1, 2]);
+/// assert_eq!(r.next_u64(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Xoroshiro128Plus {
+    s: [u64; 2],
+}
+
+/// Xoroshiro64* Generator
+///
+/// The smallest generator here, with 64 bits of state and a period of `2^64 - 1`.
+/// Best used for making 32 bit floats.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use micro_rand::{RandomSource, Xoroshiro64Star};
+///
+/// // Make a new generator from its state
+/// let mut r = Xoroshiro64Star::new([1, 2]);
+/// assert_eq!(r.next_u32(), 2654435771);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Xoroshiro64Star {
+    s: [u32; 2],
+}
+
+impl_xoshiro!(
+    Xoshiro256StarStar,
+    u64,
+    4,
+    32,
+    XOSHIRO256_JUMP,
+    XOSHIRO256_LONG_JUMP,
+    "2^128",
+    "2^192"
+);
+impl_xoshiro!(
+    Xoshiro256Plus,
+    u64,
+    4,
+    32,
+    XOSHIRO256_JUMP,
+    XOSHIRO256_LONG_JUMP,
+    "2^128",
+    "2^192"
+);
+impl_xoshiro!(
+    Xoshiro128StarStar,
+    u32,
+    4,
+    16,
+    XOSHIRO128_JUMP,
+    XOSHIRO128_LONG_JUMP,
+    "2^64",
+    "2^96"
+);
+impl_xoshiro!(
+    Xoroshiro128Plus,
+    u64,
+    2,
+    16,
+    XOROSHIRO128_JUMP,
+    XOROSHIRO128_LONG_JUMP,
+    "2^64",
+    "2^96"
+);
+impl_xoshiro!(
+    Xoroshiro64Star,
+    u32,
+    2,
+    8,
+    XOROSHIRO64_JUMP,
+    XOROSHIRO64_LONG_JUMP,
+    "2^32",
+    "2^48"
+);
+
+const XOSHIRO256_JUMP: [u64; 4] = [
+    0x180e_c6d3_3cfd_0aba,
+    0xd5a6_1266_f0c9_392c,
+    0xa958_2618_e03f_c9aa,
+    0x39ab_dc45_29b1_661c,
+];
+const XOSHIRO256_LONG_JUMP: [u64; 4] = [
+    0x76e1_5d3e_fefd_cbbf,
+    0xc500_4e44_1c52_2fb3,
+    0x7771_0069_854e_e241,
+    0x3910_9bb0_2acb_e635,
+];
+const XOSHIRO128_JUMP: [u32; 4] = [0x8764_000b, 0xf542_d2d3, 0x6fa0_35c3, 0x77f2_db5b];
+const XOSHIRO128_LONG_JUMP: [u32; 4] = [0xb523_952e, 0x0b6f_099f, 0xccf5_a0ef, 0x1c58_0662];
+const XOROSHIRO128_JUMP: [u64; 2] = [0xdf90_0294_d8f5_54a5, 0x1708_65df_4b32_01fc];
+const XOROSHIRO128_LONG_JUMP: [u64; 2] = [0xd2a9_8b26_625e_ee7b, 0xdddf_9b10_90aa_7ac1];
+
+// The reference code has no jump functions for xoroshiro64*, these are `x^(2^32)` and `x^(2^48)`
+// mod the characteristic polynomial of its state transition, found the same way as the others.
+// `test_xoroshiro64star_jump_steps` checks the jump against 2^32 steps, and the long jump against 2^16 jumps
+const XOROSHIRO64_JUMP: [u32; 2] = [0x77fc_d1a0, 0x4cbf_99bd];
+const XOROSHIRO64_LONG_JUMP: [u32; 2] = [0x3f1f_8b95, 0xb4e7_e463];
+
+impl Xoshiro256StarStar {
+    fn step(&mut self) -> u64 {
+        let out = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+
+        out
+    }
+}
+
+impl Xoshiro256Plus {
+    fn step(&mut self) -> u64 {
+        let out = self.s[0].wrapping_add(self.s[3]);
+        let t = self.s[1] << 17;
+
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+
+        out
+    }
+}
+
+impl Xoshiro128StarStar {
+    fn step(&mut self) -> u32 {
+        let out = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 9;
+
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(11);
+
+        out
+    }
+}
+
+impl Xoroshiro128Plus {
+    fn step(&mut self) -> u64 {
+        let [s0, mut s1] = self.s;
+        let out = s0.wrapping_add(s1);
+
+        s1 ^= s0;
+        self.s[0] = s0.rotate_left(24) ^ s1 ^ (s1 << 16);
+        self.s[1] = s1.rotate_left(37);
+
+        out
+    }
+}
+
+impl Xoroshiro64Star {
+    fn step(&mut self) -> u32 {
+        let [s0, mut s1] = self.s;
+        let out = s0.wrapping_mul(0x9e37_79bb);
+
+        s1 ^= s0;
+        self.s[0] = s0.rotate_left(26) ^ s1 ^ (s1 << 9);
+        self.s[1] = s1.rotate_left(13);
+
+        out
+    }
+}
+
+impl RandomSource for Xoshiro256StarStar {
+    /// The high 32 bits of [`RandomSource::next_u64`]
+    fn next_u32(&mut self) -> u32 {
+        (self.step() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.step()
+    }
+}
+
+impl RandomSource for Xoshiro256Plus {
+    /// The high 32 bits of [`RandomSource::next_u64`], as the low bits are weak
+    fn next_u32(&mut self) -> u32 {
+        (self.step() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.step()
+    }
+}
+
+impl RandomSource for Xoshiro128StarStar {
+    fn next_u32(&mut self) -> u32 {
+        self.step()
+    }
+
+    /// Made from two outputs, high bits first
+    fn next_u64(&mut self) -> u64 {
+        (self.step() as u64) << 32 | self.step() as u64
+    }
+}
+
+impl RandomSource for Xoroshiro128Plus {
+    /// The high 32 bits of [`RandomSource::next_u64`], as the low bits are weak
+    fn next_u32(&mut self) -> u32 {
+        (self.step() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.step()
+    }
+}
+
+impl RandomSource for Xoroshiro64Star {
+    fn next_u32(&mut self) -> u32 {
+        self.step()
+    }
+
+    /// Made from two outputs, high bits first
+    fn next_u64(&mut self) -> u64 {
+        (self.step() as u64) << 32 | self.step() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SplitMix64;
+
+    #[test]
+    fn test_xoshiro256starstar_known_answer() {
+        // From the reference xoshiro256starstar.c
+        let mut r = Xoshiro256StarStar::new([1, 2, 3, 4]);
+        let expected = [
+            11520,
+            0,
+            1509978240,
+            1215971899390074240,
+            1216172134540287360,
+            607988272756665600,
+            16172922978634559625,
+            8476171486693032832,
+            10595114339597558777,
+            2904607092377533576,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u64(), *i);
+        }
+    }
+
+    #[test]
+    fn test_xoshiro256plus_known_answer() {
+        // From the reference xoshiro256plus.c
+        let mut r = Xoshiro256Plus::new([1, 2, 3, 4]);
+        let expected = [
+            5,
+            211106232532999,
+            211106635186183,
+            9223759065350669058,
+            9250833439874351877,
+            13862484359527728515,
+            2346507365006083650,
+            1168864526675804870,
+            34095955243042024,
+            3466914240207415127,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u64(), *i);
+        }
+    }
+
+    #[test]
+    fn test_xoshiro128starstar_known_answer() {
+        // From the reference xoshiro128starstar.c
+        let mut r = Xoshiro128StarStar::new([1, 2, 3, 4]);
+        let expected = [
+            11520, 0, 5927040, 70819200, 2031721883, 1637235492, 1287239034, 3734860849,
+            3729100597, 4258142804,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u32(), *i);
+        }
+    }
+
+    #[test]
+    fn test_xoroshiro128plus_known_answer() {
+        // From the reference xoroshiro128plus.c
+        let mut r = Xoroshiro128Plus::new([1, 2]);
+        let expected = [
+            3,
+            412333834243,
+            2360170716294286339,
+            9295852285959843169,
+            2797080929874688578,
+            6019711933173041966,
+            3076529664176959358,
+            3521761819100106140,
+            7493067640054542992,
+            920801338098114767,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u64(), *i);
+        }
+    }
+
+    #[test]
+    fn test_xoroshiro64star_known_answer() {
+        // From the reference xoroshiro64star.c
+        let mut r = Xoroshiro64Star::new([1, 2]);
+        let expected = [
+            2654435771, 327208753, 4063491769, 4259754937, 261922412, 168123673, 552743735,
+            1672597395, 1031040050, 2755315674,
+        ];
+        for i in expected.iter() {
+            assert_eq!(r.next_u32(), *i);
+        }
+    }
+
+    #[test]
+    fn test_jump() {
+        let mut r = Xoshiro256StarStar::new([1, 2, 3, 4]);
+        r.jump();
+        assert_eq!(
+            r.s,
+            [
+                10122426448480695249,
+                8079205330032121950,
+                7289065458748526725,
+                9477464255293849680
+            ]
+        );
+
+        let mut r = Xoshiro256Plus::new([1, 2, 3, 4]);
+        r.long_jump();
+        assert_eq!(
+            r.s,
+            [
+                678511610814637056,
+                15850499779492529430,
+                6002989639035333134,
+                3559352929785830385
+            ]
+        );
+
+        let mut r = Xoshiro128StarStar::new([1, 2, 3, 4]);
+        r.jump();
+        assert_eq!(r.s, [2843103750, 2038079848, 1533207345, 44816753]);
+        let mut r = Xoshiro128StarStar::new([1, 2, 3, 4]);
+        r.long_jump();
+        assert_eq!(r.s, [1611968294, 2125834322, 966769569, 3193880526]);
+
+        let mut r = Xoroshiro128Plus::new([1, 2]);
+        r.jump();
+        assert_eq!(r.s, [7420758724034209717, 9442990532527272306]);
+        let mut r = Xoroshiro128Plus::new([1, 2]);
+        r.long_jump();
+        assert_eq!(r.s, [4387707342976528954, 3072119776036644419]);
+
+        let mut r = Xoroshiro64Star::new([1, 2]);
+        r.jump();
+        assert_eq!(r.s, [3370103944, 2537896034]);
+        let mut r = Xoroshiro64Star::new([1, 2]);
+        r.long_jump();
+        assert_eq!(r.s, [879734759, 2063398418]);
+    }
+
+    #[test]
+    fn test_xoroshiro64star_jumps_add_up() {
+        // 2^16 jumps of 2^32 steps should be one long jump of 2^48 steps
+        let mut a = Xoroshiro64Star::new([1, 2]);
+        let mut b = Xoroshiro64Star::new([1, 2]);
+        for _ in 0..1 << 16 {
+            a.jump();
+        }
+        b.long_jump();
+        assert_eq!(a.s, b.s);
+    }
+
+    #[test]
+    #[ignore = "steps the generator 2^32 times, run with --release --ignored"]
+    fn test_xoroshiro64star_jump_steps() {
+        let mut a = Xoroshiro64Star::new([1, 2]);
+        let mut b = Xoroshiro64Star::new([1, 2]);
+        a.jump();
+        for _ in 0..1u64 << 32 {
+            b.step();
+        }
+        assert_eq!(a.s, b.s);
+    }
+
+    #[test]
+    fn test_zero_state() {
+        assert_eq!(
+            Xoshiro256StarStar::try_new([0; 4]).err(),
+            Some(ParamError::FixedPointSeed)
+        );
+        assert_eq!(
+            Xoroshiro64Star::try_new([0; 2]).err(),
+            Some(ParamError::FixedPointSeed)
+        );
+        assert!(Xoroshiro64Star::try_new([0, 1]).is_ok());
+    }
+
+    #[test]
+    fn test_seed() {
+        let mut r = Xoshiro256StarStar::seed_from_u64(1234);
+        let mut expand = SplitMix64::new(1234);
+        for i in r.s.iter() {
+            assert_eq!(*i, expand.next_u64());
+        }
+        r.next_u64();
+
+        let mut seed = [0; 16];
+        seed[0] = 1;
+        seed[8] = 2;
+        let r = Xoroshiro128Plus::from_seed(seed);
+        assert_eq!(r.s, [1, 2]);
+
+        let r = Xoroshiro64Star::from_seed([0; 8]);
+        assert_eq!(r.s, Xoroshiro64Star::seed_from_u64(0).s);
+        assert_ne!(r.s, [0, 0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_state_panics() {
+        Xoshiro128StarStar::new([0; 4]);
+    }
+}